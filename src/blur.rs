@@ -0,0 +1,204 @@
+/**
+Blurs a coverage map in place with three box blurs in a row, which is close to a Gaussian blur with standard deviation `sigma`.
+
+Each box blur is done horizontally and then vertically, pixels outside of the map count as 0.
+*/
+pub(crate) fn gaussian_blur(alpha: &mut [f32], width: usize, height: usize, sigma: f32) {
+  if sigma <= 0.0 || width == 0 || height == 0 {
+    return;
+  }
+  let mut scratch = vec![0.0; alpha.len()];
+  for size in box_sizes_for_gauss(sigma, 3) {
+    let radius = (size - 1) / 2;
+    if radius == 0 {
+      continue;
+    }
+    box_blur_h(alpha, &mut scratch, width, height, radius);
+    box_blur_v(&scratch, alpha, width, height, radius);
+  }
+}
+
+/** Sizes of `n` box blurs whose combined variance matches a Gaussian with standard deviation `sigma`. */
+fn box_sizes_for_gauss(sigma: f32, n: usize) -> Vec<usize> {
+  let n_f = n as f32;
+  let ideal = (12.0 * sigma * sigma / n_f + 1.0).sqrt();
+  let mut lower = ideal.floor() as usize;
+  if lower.is_multiple_of(2) {
+    lower = lower.saturating_sub(1).max(1);
+  }
+  let upper = lower + 2;
+  let l = lower as f32;
+  let lower_count =
+    ((12.0 * sigma * sigma - n_f * l * l - 4.0 * n_f * l - 3.0 * n_f) / (-4.0 * l - 4.0)).round();
+  (0..n)
+    .map(|i| {
+      if (i as f32) < lower_count {
+        lower
+      } else {
+        upper
+      }
+    })
+    .collect()
+}
+
+fn box_blur_h(src: &[f32], dst: &mut [f32], width: usize, height: usize, radius: usize) {
+  let scale = 1.0 / (radius * 2 + 1) as f32;
+  for y in 0..height {
+    let row = &src[y * width..(y + 1) * width];
+    let mut sum: f32 = row[..radius.min(width)].iter().sum();
+    for x in 0..width {
+      if x + radius < width {
+        sum += row[x + radius];
+      }
+      dst[y * width + x] = sum * scale;
+      if x >= radius {
+        sum -= row[x - radius];
+      }
+    }
+  }
+}
+
+fn box_blur_v(src: &[f32], dst: &mut [f32], width: usize, height: usize, radius: usize) {
+  let scale = 1.0 / (radius * 2 + 1) as f32;
+  for x in 0..width {
+    let mut sum: f32 = (0..radius.min(height)).map(|y| src[y * width + x]).sum();
+    for y in 0..height {
+      if y + radius < height {
+        sum += src[(y + radius) * width + x];
+      }
+      dst[y * width + x] = sum * scale;
+      if y >= radius {
+        sum -= src[(y - radius) * width + x];
+      }
+    }
+  }
+}
+
+/**
+Grows (positive `amount`) or shrinks (negative `amount`) a coverage map by taking the max or min of every pixel within a circle of that radius.
+
+The circle is split into one horizontal chord per row, and each chord is a range query on a sparse table of its source row, so the cost grows with the radius instead of its square.
+*/
+pub(crate) fn spread(alpha: &[f32], width: usize, height: usize, amount: i16) -> Vec<f32> {
+  if amount == 0 {
+    return alpha.to_vec();
+  }
+  let r = amount.unsigned_abs() as usize;
+  let grow = amount > 0;
+  let pick = |a: f32, b: f32| if grow { a.max(b) } else { a.min(b) };
+  //Half the width of the circle at each row offset, the pixels with dx² + dy² <= r².
+  let chords: Vec<usize> = (0..=r).map(|dy| (r * r - dy * dy).isqrt()).collect();
+
+  let mut result = vec![if grow { 0.0 } else { 1.0 }; alpha.len()];
+  let mut table: Vec<Vec<f32>> = Vec::new();
+  for source_y in 0..height {
+    //Level k holds the max or min of the 2^k pixels starting at each x.
+    table.clear();
+    table.push(alpha[source_y * width..(source_y + 1) * width].to_vec());
+    let mut run = 1;
+    while run * 2 <= width {
+      let previous = &table[table.len() - 1];
+      let level = (0..=width - run * 2)
+        .map(|x| pick(previous[x], previous[x + run]))
+        .collect();
+      table.push(level);
+      run *= 2;
+    }
+    let query = |from: usize, to: usize| {
+      let level = (to - from + 1).ilog2() as usize;
+      pick(table[level][from], table[level][to + 1 - (1 << level)])
+    };
+    //Every row whose circle reaches this one takes the chord at that offset.
+    for y in source_y.saturating_sub(r)..(source_y + r + 1).min(height) {
+      let chord = chords[source_y.abs_diff(y)];
+      for x in 0..width {
+        //Outside of the map is empty, so shrinking eats into shapes that touch the edge.
+        let value = if !grow && (x < chord || x + chord >= width) {
+          0.0
+        } else {
+          query(x.saturating_sub(chord), (x + chord).min(width - 1))
+        };
+        let pixel = &mut result[y * width + x];
+        *pixel = pick(*pixel, value);
+      }
+    }
+  }
+  if !grow {
+    //Circles of rows this close to the top or bottom reach past the map.
+    for y in (0..height).filter(|y| *y < r || y + r >= height) {
+      result[y * width..(y + 1) * width].fill(0.0);
+    }
+  }
+  result
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn box_sizes_match_sigma() {
+    assert_eq!(box_sizes_for_gauss(1.0, 3), vec![1, 1, 3]);
+    assert_eq!(box_sizes_for_gauss(2.0, 3), vec![3, 3, 5]);
+    assert_eq!(box_sizes_for_gauss(5.0, 3), vec![9, 9, 11]);
+  }
+  #[test]
+  fn blur_keeps_total_and_symmetry() {
+    let (w, h) = (21, 21);
+    let mut alpha = vec![0.0; w * h];
+    alpha[10 * w + 10] = 1.0;
+    gaussian_blur(&mut alpha, w, h, 2.0);
+    let total: f32 = alpha.iter().sum();
+    assert!((total - 1.0).abs() < 0.001);
+    assert!(alpha[10 * w + 10] < 0.2);
+    assert_eq!(alpha[10 * w + 7], alpha[10 * w + 13]);
+    assert_eq!(alpha[7 * w + 10], alpha[10 * w + 7]);
+    assert!(alpha[10 * w + 9] > alpha[10 * w + 8]);
+  }
+  #[test]
+  fn zero_sigma_is_noop() {
+    let mut alpha = vec![0.0, 1.0, 0.5, 0.0];
+    gaussian_blur(&mut alpha, 2, 2, 0.0);
+    assert_eq!(alpha, vec![0.0, 1.0, 0.5, 0.0]);
+  }
+  #[rustfmt::skip]
+  #[test]
+  fn spread_grows_and_shrinks() {
+    let alpha = vec![
+      0.0, 0.0, 0.0, 0.0, 0.0,
+      0.0, 0.0, 0.0, 0.0, 0.0,
+      0.0, 0.0, 1.0, 0.0, 0.0,
+      0.0, 0.0, 0.0, 0.0, 0.0,
+      0.0, 0.0, 0.0, 0.0, 0.0,
+    ];
+    let grown = spread(&alpha, 5, 5, 1);
+    assert_eq!(grown, vec![
+      0.0, 0.0, 0.0, 0.0, 0.0,
+      0.0, 0.0, 1.0, 0.0, 0.0,
+      0.0, 1.0, 1.0, 1.0, 0.0,
+      0.0, 0.0, 1.0, 0.0, 0.0,
+      0.0, 0.0, 0.0, 0.0, 0.0,
+    ]);
+    assert_eq!(spread(&grown, 5, 5, -1), alpha);
+  }
+  #[test]
+  fn spread_matches_circle() {
+    let (w, h) = (9, 7);
+    let mut alpha = vec![0.0; w * h];
+    alpha[3 * w + 4] = 1.0;
+    let grown = spread(&alpha, w, h, 3);
+    for y in 0..h {
+      for x in 0..w {
+        let (dx, dy) = (x as i32 - 4, y as i32 - 3);
+        let inside = dx * dx + dy * dy <= 9;
+        assert_eq!(
+          grown[y * w + x],
+          if inside { 1.0 } else { 0.0 },
+          "({x}, {y})"
+        );
+      }
+    }
+    let shrunk = spread(&grown, w, h, -3);
+    assert_eq!(shrunk.iter().sum::<f32>(), 1.0);
+  }
+}
@@ -0,0 +1,235 @@
+use crate::{
+  colors::mix_color,
+  error::{check_range, SpriteorError, LENGTH_RANGE},
+};
+
+/** How the border of a [`SpriteorRectOp`](crate::SpriteorRectOp) or an H or V line op is drawn, like CSS `border-style`. */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BorderStyle {
+  #[default]
+  Solid,
+  /**
+  Dashes `dash` pixels long with `gap` pixels between them, both in range [0, 4096], measured along the middle of the border and following rounded corners.
+
+  Like CSS the pattern is stretched slightly on each side so it ends the same way at both corners, with a dash centered on every corner.
+  */
+  Dashed { dash: u16, gap: u16 },
+  /** Round dots as wide as the border with `gap` pixels between them, in range [0, 4096], spaced like `Dashed` with a dot centered on every corner. */
+  Dotted { gap: u16 },
+  /** Two lines with a gap between them, each a third of the border width. Borders narrower than 3 pixels are solid. */
+  Double,
+  /** Looks carved into the surface, the outer half of the top and left sides is darker and the inner half lighter. */
+  Groove,
+  /** Opposite of `Groove`, looks like it sticks out of the surface. */
+  Ridge,
+  /** Top and left sides are darker, bottom and right lighter, so the rect looks pressed in. */
+  Inset,
+  /** Opposite of `Inset`, the rect looks raised. */
+  Outset,
+}
+
+impl BorderStyle {
+  pub(crate) fn validate(&self) -> Result<(), SpriteorError> {
+    match self {
+      BorderStyle::Dashed { dash, gap } => {
+        check_range("dash", *dash as i32, LENGTH_RANGE)?;
+        check_range("gap", *gap as i32, LENGTH_RANGE)
+      }
+      BorderStyle::Dotted { gap } => check_range("gap", *gap as i32, LENGTH_RANGE),
+      _ => Ok(()),
+    }
+  }
+  /**
+  Color of the border at a pixel, None where the style leaves a gap.
+
+  `position` is the distance clockwise along the middle of the border from the corner the pattern starts at, and `length` the distance to the corner it ends at. `depth` is how far the pixel center is into the border in range [0, 1] from the outer to the inner edge, `width` is the border width there. `lit` is true on the top and left sides, where light from the top left hits the outside of the rect.
+  */
+  pub(crate) fn color_at(
+    &self,
+    color: &[u8; 4],
+    position: f32,
+    length: f32,
+    depth: f32,
+    width: f32,
+    lit: bool,
+  ) -> Option<[u8; 4]> {
+    let (light, dark) = (shade(color, [255, 255, 255]), shade(color, [0, 0, 0]));
+    match self {
+      BorderStyle::Solid => Some(*color),
+      BorderStyle::Dashed { dash, gap } => {
+        let pattern = *dash as f32 + *gap as f32;
+        let Some(period) = fitted_period(length, pattern) else {
+          return Some(*color);
+        };
+        let dash = *dash as f32 * period / pattern;
+        ((position + dash / 2.0).rem_euclid(period) < dash).then_some(*color)
+      }
+      BorderStyle::Dotted { gap } => {
+        let period = fitted_period(length, width + *gap as f32)?;
+        let along = position.rem_euclid(period);
+        let along = along.min(period - along);
+        let across = depth * width - width / 2.0;
+        (along.hypot(across) < width / 2.0).then_some(*color)
+      }
+      BorderStyle::Double => {
+        (width < 3.0 || !(1.0 / 3.0..=2.0 / 3.0).contains(&depth)).then_some(*color)
+      }
+      BorderStyle::Groove | BorderStyle::Ridge => {
+        let outer_half = depth < 0.5;
+        let darker = (outer_half == lit) == (*self == BorderStyle::Groove);
+        Some(if darker { dark } else { light })
+      }
+      BorderStyle::Inset => Some(if lit { dark } else { light }),
+      BorderStyle::Outset => Some(if lit { light } else { dark }),
+    }
+  }
+}
+
+/** Where a border is drawn relative to the edge of a shape, like the stroke alignment of design tools. */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StrokeAlign {
+  /** The border is inside of the edge and the shape keeps its size. */
+  #[default]
+  Inside,
+  /** The border is centered on the edge, the odd pixel of odd widths goes inside. */
+  Center,
+  /** The border is outside of the edge, so the fill keeps the full size of the shape. Outside borders can extend into the sprite margin. */
+  Outside,
+}
+
+impl StrokeAlign {
+  /** Splits a border width into the pixels (inside, outside) of the edge. */
+  pub(crate) fn split(&self, width: u16) -> (u16, u16) {
+    match self {
+      StrokeAlign::Inside => (width, 0),
+      StrokeAlign::Center => (width - width / 2, width / 2),
+      StrokeAlign::Outside => (0, width),
+    }
+  }
+}
+
+/** Shape of a polygon border where two edges meet, like SVG `stroke-linejoin`. */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StrokeJoin {
+  /** Sharp corners. Corners so sharp that the point would be more than 4 times the border width long are beveled instead, like SVG. */
+  #[default]
+  Mitre,
+  Round,
+  /** Corners are cut off straight. */
+  Bevel,
+}
+
+/** Shape of the ends of a line, like SVG `stroke-linecap`. */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StrokeCap {
+  /** The line ends exactly at the end points, so the end pixels are only half covered. */
+  Butt,
+  Round,
+  /** The line reaches past the end points by half its width, so pixels at the end points are fully covered. */
+  #[default]
+  Square,
+}
+
+/** `period` stretched or squeezed so the pattern repeats a whole number of times over `length`, None if there is no pattern. */
+fn fitted_period(length: f32, period: f32) -> Option<f32> {
+  if period <= 0.0 || length <= 0.0 {
+    return None;
+  }
+  Some(length / (length / period).round().max(1.0))
+}
+
+/** Moves the color 40% towards `target`, keeping its alpha. */
+fn shade(color: &[u8; 4], target: [u8; 3]) -> [u8; 4] {
+  mix_color(color, &[target[0], target[1], target[2], color[3]], 0.4)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const WHITE: [u8; 4] = [255, 255, 255, 255];
+
+  #[test]
+  fn dashes_fit_the_side() {
+    let dashed = BorderStyle::Dashed { dash: 3, gap: 2 };
+    //42 pixels fit 8.4 repetitions, stretched to 8 of 5.25 pixels with half a dash at both ends.
+    let drawn: Vec<bool> = (0..43)
+      .map(|p| {
+        dashed
+          .color_at(&WHITE, p as f32, 42.0, 0.5, 1.0, true)
+          .is_some()
+      })
+      .collect();
+    assert_eq!(
+      drawn[..8],
+      [true, true, false, false, true, true, true, false]
+    );
+    assert!(drawn.iter().eq(drawn.iter().rev()));
+    assert_eq!(
+      BorderStyle::Dashed { dash: 0, gap: 0 }.color_at(&WHITE, 1.0, 42.0, 0.5, 1.0, true),
+      Some(WHITE)
+    );
+    //Longer than the side, the pattern is squeezed into it once with half a dash at both ends.
+    let long = BorderStyle::Dashed {
+      dash: 40000,
+      gap: 40000,
+    };
+    assert_eq!(
+      long.color_at(&WHITE, 0.0, 42.0, 0.5, 1.0, true),
+      Some(WHITE)
+    );
+  }
+  #[test]
+  fn dots_are_round() {
+    let dotted = BorderStyle::Dotted { gap: 2 };
+    let drawn = |position: f32, depth: f32, width: f32| {
+      dotted
+        .color_at(&WHITE, position, 100.0, depth, width, true)
+        .is_some()
+    };
+    assert!(drawn(0.0, 0.5, 1.0) && !drawn(1.0, 0.5, 1.0) && !drawn(2.0, 0.5, 1.0));
+    assert!(drawn(3.0, 0.5, 1.0));
+    //4 pixels wide, the corners of the 4 x 4 square around the dot are cut off.
+    assert!(drawn(0.5, 0.125, 4.0) && drawn(1.5, 0.375, 4.0));
+    assert!(!drawn(1.5, 0.125, 4.0) && !drawn(-1.5, 0.875, 4.0));
+  }
+  #[test]
+  fn double_leaves_middle_third_empty() {
+    let double = BorderStyle::Double;
+    let depths = [0.5 / 3.0, 1.5 / 3.0, 2.5 / 3.0];
+    let drawn: Vec<bool> = depths
+      .iter()
+      .map(|depth| {
+        double
+          .color_at(&WHITE, 0.0, 10.0, *depth, 3.0, true)
+          .is_some()
+      })
+      .collect();
+    assert_eq!(drawn, vec![true, false, true]);
+    assert!(double.color_at(&WHITE, 0.0, 10.0, 0.5, 2.0, true).is_some());
+  }
+  #[test]
+  fn two_tone_styles() {
+    let gray = [128, 128, 128, 255];
+    let (light, dark) = (shade(&gray, [255, 255, 255]), shade(&gray, [0, 0, 0]));
+    let at = |style: BorderStyle, depth: f32, lit: bool| {
+      style.color_at(&gray, 0.0, 10.0, depth, 2.0, lit).unwrap()
+    };
+    assert_eq!(at(BorderStyle::Inset, 0.5, true), dark);
+    assert_eq!(at(BorderStyle::Inset, 0.5, false), light);
+    assert_eq!(at(BorderStyle::Outset, 0.5, true), light);
+    assert_eq!(at(BorderStyle::Groove, 0.25, true), dark);
+    assert_eq!(at(BorderStyle::Groove, 0.75, true), light);
+    assert_eq!(at(BorderStyle::Groove, 0.25, false), light);
+    assert_eq!(at(BorderStyle::Ridge, 0.25, true), light);
+    assert_eq!(at(BorderStyle::Ridge, 0.75, false), light);
+    assert_eq!(light[3], 255);
+  }
+  #[test]
+  fn stroke_align_splits_width() {
+    assert_eq!(StrokeAlign::Inside.split(3), (3, 0));
+    assert_eq!(StrokeAlign::Center.split(3), (2, 1));
+    assert_eq!(StrokeAlign::Center.split(4), (2, 2));
+    assert_eq!(StrokeAlign::Outside.split(3), (0, 3));
+  }
+}
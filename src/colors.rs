@@ -1,9 +1,9 @@
 pub(crate) fn add_color(a: &[u8; 4], b: &[u8; 4]) -> [u8; 4] {
   if a[3] == 0 || b[3] == 255 {
-    return b.clone();
+    return *b;
   }
   if b[3] == 0 {
-    return a.clone();
+    return *a;
   }
 
   let sum_alpha = a[3] as f32 + b[3] as f32;
@@ -18,23 +18,52 @@ pub(crate) fn add_color(a: &[u8; 4], b: &[u8; 4]) -> [u8; 4] {
   ]
 }
 
-pub(crate) fn add_color_set_pixel(values: &mut Vec<u8>, index: &usize, color: &[u8; 4]) {
+pub(crate) fn add_color_set_pixel(values: &mut [u8], index: &usize, color: &[u8; 4]) {
   let color = add_color(
     &[
-      values[(index * 4)],
+      values[index * 4],
       values[(index * 4) + 1],
       values[(index * 4) + 2],
       values[(index * 4) + 3],
     ],
     color,
   );
-  values[(index * 4)] = color[0];
+  values[index * 4] = color[0];
   values[(index * 4) + 1] = color[1];
   values[(index * 4) + 2] = color[2];
   values[(index * 4) + 3] = color[3];
 }
+/** Linear interpolation from a to b, t in range [0, 1]. */
+pub(crate) fn mix_color(a: &[u8; 4], b: &[u8; 4], t: f32) -> [u8; 4] {
+  if t <= 0.0 {
+    return *a;
+  }
+  if t >= 1.0 {
+    return *b;
+  }
+  [
+    (a[0] as f32 + (b[0] as f32 - a[0] as f32) * t).round() as u8,
+    (a[1] as f32 + (b[1] as f32 - a[1] as f32) * t).round() as u8,
+    (a[2] as f32 + (b[2] as f32 - a[2] as f32) * t).round() as u8,
+    (a[3] as f32 + (b[3] as f32 - a[3] as f32) * t).round() as u8,
+  ]
+}
+
+/** Multiplies the alpha channel by factor, used for partially covered pixels. */
+pub(crate) fn scale_alpha(color: &[u8; 4], factor: f32) -> [u8; 4] {
+  if factor >= 1.0 {
+    return *color;
+  }
+  [
+    color[0],
+    color[1],
+    color[2],
+    (color[3] as f32 * factor.max(0.0)).round() as u8,
+  ]
+}
+
 // pub(crate) fn add_color_set_pixel_x_y(
-//   values: &mut Vec<u8>,
+//   values: &mut [u8],
 //   x: &u16,
 //   y: &u16,
 //   color: &[u8; 4],
@@ -98,4 +127,18 @@ mod tests {
     print_colors(&a, &b, &result);
     assert_eq!(result, [0, 255, 0, 255]);
   }
+  #[test]
+  fn mix_color_endpoints_and_middle() {
+    let a = [255, 0, 0, 255];
+    let b = [0, 0, 255, 127];
+    assert_eq!(mix_color(&a, &b, 0.0), a);
+    assert_eq!(mix_color(&a, &b, 1.0), b);
+    assert_eq!(mix_color(&a, &b, 0.5), [128, 0, 128, 191]);
+  }
+  #[test]
+  fn scale_alpha_keeps_color() {
+    assert_eq!(scale_alpha(&[10, 20, 30, 200], 0.5), [10, 20, 30, 100]);
+    assert_eq!(scale_alpha(&[10, 20, 30, 200], 1.0), [10, 20, 30, 200]);
+    assert_eq!(scale_alpha(&[10, 20, 30, 200], 0.0), [10, 20, 30, 0]);
+  }
 }
@@ -3,6 +3,8 @@ use std::io;
 use std::io::Write;
 
 use crate::maths::map_range;
+#[cfg(test)]
+use crate::spriteor::{Spriteor, SpriteorOperation, SpriteorSettings};
 
 // pub(crate) fn print_pixel_vals(prefix: &str, pixels: &Vec<u8>) {
 //   let count = pixels.len() / 4;
@@ -24,14 +26,16 @@ use crate::maths::map_range;
 //   }
 // }
 
-pub(crate) fn print_points(prefix: &str, pts: &Vec<(f32, f32)>) {
-  let pre = if prefix.len() > 0 {
+#[cfg(test)]
+#[allow(dead_code)]
+pub(crate) fn print_points(prefix: &str, pts: &[(f32, f32)]) {
+  let pre = if !prefix.is_empty() {
     format!("{}:  ", prefix)
   } else {
     "".to_owned()
   };
-  for i in 0..pts.len() {
-    println!("{}: i:{}  ({:#2}, {:#2})", pre, i, pts[i].0, pts[i].1);
+  for (i, pt) in pts.iter().enumerate() {
+    println!("{}: i:{}  ({:#2}, {:#2})", pre, i, pt.0, pt.1);
   }
 }
 
@@ -40,7 +44,7 @@ pub(crate) fn print_points(prefix: &str, pts: &Vec<(f32, f32)>) {
  * Used for generating test data with a quarter as many values entered.
  */
 #[cfg(test)]
-pub(crate) fn pixels_to_values(pixels: &Vec<u8>, color: &[u8; 4]) -> Vec<u8> {
+pub(crate) fn pixels_to_values(pixels: &[u8], color: &[u8; 4]) -> Vec<u8> {
   let mut values = vec![0; pixels.len() * 4];
   for (pi, p) in pixels.iter().enumerate() {
     if p > &0 {
@@ -52,7 +56,7 @@ pub(crate) fn pixels_to_values(pixels: &Vec<u8>, color: &[u8; 4]) -> Vec<u8> {
   values
 }
 #[cfg(test)]
-pub(crate) fn modify_pixels(values: &mut Vec<u8>, pixels: &Vec<u8>, color: &[u8; 4]) {
+pub(crate) fn modify_pixels(values: &mut [u8], pixels: &[u8], color: &[u8; 4]) {
   if values.len() != pixels.len() * 4 {
     panic!("Modify pixels failed, values and pixel arrays not sized correctly.");
   }
@@ -76,14 +80,11 @@ pub(crate) fn print_colors(a: &[u8; 4], b: &[u8; 4], c: &[u8; 4]) {
     "{} ",
     format!("{: ^3}", b[3]).on_truecolor(b[0], b[1], b[2])
   );
-  print!(
-    "{}\n",
-    format!("{: ^3}", c[3]).on_truecolor(c[0], c[1], c[2])
-  );
+  println!("{}", format!("{: ^3}", c[3]).on_truecolor(c[0], c[1], c[2]));
   io::stdout().flush().unwrap();
 }
 
-pub(crate) fn print_matrix(values: &Vec<u8>, width: u16, mode: u8) {
+pub(crate) fn print_matrix(values: &[u8], width: u16, mode: u8) {
   let w = width as usize;
   io::stdout().flush().unwrap();
   let mut rowi = 0;
@@ -119,13 +120,13 @@ pub(crate) fn print_matrix(values: &Vec<u8>, width: u16, mode: u8) {
         };
         print!(
           "{}",
-          format!("{}", t).on_truecolor(values[i], values[i + 1], values[i + 2])
+          t.on_truecolor(values[i], values[i + 1], values[i + 2])
         )
       }
       _ => {
         print!(
           "{}",
-          format!(". ").on_truecolor(values[i], values[i + 1], values[i + 2])
+          ". ".on_truecolor(values[i], values[i + 1], values[i + 2])
         )
       }
     }
@@ -133,8 +134,52 @@ pub(crate) fn print_matrix(values: &Vec<u8>, width: u16, mode: u8) {
     rowi += 1;
     if rowi == w {
       rowi = 0;
-      print!("\n");
+      println!();
       io::stdout().flush().unwrap();
     }
   }
 }
+
+/** Finalizes a sprite with `settings` and `operations` and turns every row of it into text, with the character `classify` picks for each pixel. */
+#[cfg(test)]
+pub(crate) fn render_rows(
+  settings: &SpriteorSettings,
+  operations: Vec<SpriteorOperation>,
+  classify: impl Fn(&[u8]) -> char,
+) -> Vec<String> {
+  let mut spriteor = Spriteor::new(settings);
+  for operation in operations {
+    spriteor.add_operation(operation);
+  }
+  spriteor
+    .finalize()
+    .chunks(settings.width as usize * 4)
+    .map(|row| row.chunks(4).map(&classify).collect())
+    .collect()
+}
+/** Pixel classifier for [`render_rows`], empty pixels are ' ', white '#' and any other color '.'. */
+#[cfg(test)]
+pub(crate) fn white_or_color(pixel: &[u8]) -> char {
+  match pixel {
+    [_, _, _, 0] => ' ',
+    [255, 255, 255, _] => '#',
+    _ => '.',
+  }
+}
+/** Pixel classifier for [`render_rows`], empty pixels are ' ' and everything drawn '#'. */
+#[cfg(test)]
+pub(crate) fn drawn(pixel: &[u8]) -> char {
+  if pixel[3] == 0 {
+    ' '
+  } else {
+    '#'
+  }
+}
+
+#[cfg(test)]
+pub(crate) fn assert_near(a: (f32, f32), b: (f32, f32)) {
+  assert!(
+    (a.0 - b.0).abs() < 1e-5 && (a.1 - b.1).abs() < 1e-5,
+    "{a:?} != {b:?}"
+  );
+}
@@ -0,0 +1,342 @@
+use crate::{
+  blur::{gaussian_blur, spread},
+  colors::{add_color_set_pixel, scale_alpha},
+  error::{check_coordinate, check_range, SpriteorError, EFFECT_MAX},
+};
+
+/**
+Drop shadow under the operation right before it in the list.
+
+The shadow is made from the alpha of that operation, so it follows rounded corners and polygons. It is not clipped to the container the shape is drawn in and can bleed out into the sprite `margin`, which is the space to reserve for it. Several effect ops in a row all apply to the same shape, an effect op with no shape before it draws nothing.
+*/
+#[derive(Debug, Clone)]
+pub struct SpriteorShadowOp {
+  /** Pixels (x, y) the shadow is moved by, must be in range [-4096, 4096]. */
+  pub offset: (i16, i16),
+  /** Blur radius in pixels like CSS `box-shadow`, must be in range [0, 256]. The Gaussian blur uses half of it as standard deviation. */
+  pub blur_radius: u16,
+  /** Pixels the shape is grown by before blurring, must be in range [-256, 256]. Negative values shrink it. */
+  pub spread: i16,
+  pub color: [u8; 4],
+}
+impl SpriteorShadowOp {
+  pub(crate) fn validate(&self) -> Result<(), SpriteorError> {
+    check_coordinate(self.offset.0 as i32, self.offset.1 as i32)?;
+    check_range("blur_radius", self.blur_radius as i32, (0, EFFECT_MAX))?;
+    check_range("spread", self.spread as i32, (-EFFECT_MAX, EFFECT_MAX))
+  }
+
+  /**
+  Draws the shadow of `shape` into `values`, which should not contain the shape yet.
+
+  `shape` has the same size as `values` and only its alpha is used.
+  */
+  pub(crate) fn add_under(&self, values: &mut [u8], shape: &[u8], sprite_width: &u16) {
+    let width = *sprite_width as usize;
+    let height = shape.len() / 4 / width;
+    let alpha: Vec<f32> = shape.chunks(4).map(|c| c[3] as f32 / 255.0).collect();
+    let mut alpha = spread(&alpha, width, height, self.spread);
+    gaussian_blur(&mut alpha, width, height, self.blur_radius as f32 / 2.0);
+
+    let (offset_x, offset_y) = (self.offset.0 as isize, self.offset.1 as isize);
+    for y in 0..height {
+      let source_y = y as isize - offset_y;
+      if source_y < 0 || source_y >= height as isize {
+        continue;
+      }
+      for x in 0..width {
+        let source_x = x as isize - offset_x;
+        if source_x < 0 || source_x >= width as isize {
+          continue;
+        }
+        let coverage = alpha[source_y as usize * width + source_x as usize];
+        if coverage <= 0.0 {
+          continue;
+        }
+        add_color_set_pixel(
+          values,
+          &(y * width + x),
+          &scale_alpha(&self.color, coverage),
+        );
+      }
+    }
+  }
+}
+impl Default for SpriteorShadowOp {
+  fn default() -> Self {
+    SpriteorShadowOp {
+      offset: (2, 2),
+      blur_radius: 4,
+      spread: 0,
+      color: [0, 0, 0, 128],
+    }
+  }
+}
+
+/** Glow around the operation right before it in the list. Works like [`SpriteorShadowOp`] without an offset. */
+#[derive(Debug, Clone)]
+pub struct SpriteorGlowOp {
+  /** Blur radius in pixels, must be in range [0, 256]. The Gaussian blur uses half of it as standard deviation. */
+  pub blur_radius: u16,
+  /** Pixels the shape is grown by before blurring, must be in range [-256, 256]. Negative values shrink it. */
+  pub spread: i16,
+  pub color: [u8; 4],
+}
+impl SpriteorGlowOp {
+  pub(crate) fn validate(&self) -> Result<(), SpriteorError> {
+    self.as_shadow().validate()
+  }
+  pub(crate) fn as_shadow(&self) -> SpriteorShadowOp {
+    SpriteorShadowOp {
+      offset: (0, 0),
+      blur_radius: self.blur_radius,
+      spread: self.spread,
+      color: self.color,
+    }
+  }
+}
+impl Default for SpriteorGlowOp {
+  fn default() -> Self {
+    SpriteorGlowOp {
+      blur_radius: 4,
+      spread: 1,
+      color: [255, 255, 255, 192],
+    }
+  }
+}
+
+/** Shadow cast inside of a rect by its own edges, like CSS `box-shadow: inset`. It is drawn over the fill and stays inside of the border. */
+#[derive(Debug, Clone, PartialEq)]
+pub struct InnerShadow {
+  /** Pixels (x, y) the shadow is moved by, must be in range [-4096, 4096]. */
+  pub offset: (i16, i16),
+  /** Blur radius in pixels, must be in range [0, 256]. The Gaussian blur uses half of it as standard deviation. */
+  pub blur_radius: u16,
+  pub color: [u8; 4],
+}
+impl InnerShadow {
+  pub(crate) fn validate(&self) -> Result<(), SpriteorError> {
+    check_coordinate(self.offset.0 as i32, self.offset.1 as i32)?;
+    check_range("blur_radius", self.blur_radius as i32, (0, EFFECT_MAX))
+  }
+
+  /**
+  Shadow alpha for every pixel of a `width` x `height` area.
+
+  `inside` returns the coverage of the shape in range [0, 1] at a pixel relative to the top left pixel of the area. The map is padded so the blur also pulls in the shadow from outside of the area.
+  */
+  pub(crate) fn alpha_map(
+    &self,
+    width: usize,
+    height: usize,
+    inside: impl Fn(f32, f32) -> f32,
+  ) -> Vec<f32> {
+    let sigma = self.blur_radius as f32 / 2.0;
+    let pad = (sigma * 3.0).ceil() as usize + 1;
+    let (map_width, map_height) = (width + pad * 2, height + pad * 2);
+    let mut map = Vec::with_capacity(map_width * map_height);
+    for y in 0..map_height {
+      for x in 0..map_width {
+        let source_x = x as f32 - pad as f32 - self.offset.0 as f32;
+        let source_y = y as f32 - pad as f32 - self.offset.1 as f32;
+        map.push(1.0 - inside(source_x, source_y));
+      }
+    }
+    gaussian_blur(&mut map, map_width, map_height, sigma);
+
+    let mut alpha = Vec::with_capacity(width * height);
+    for y in 0..height {
+      let row = (y + pad) * map_width + pad;
+      alpha.extend_from_slice(&map[row..row + width]);
+    }
+    alpha
+  }
+}
+impl Default for InnerShadow {
+  fn default() -> Self {
+    InnerShadow {
+      offset: (0, 2),
+      blur_radius: 4,
+      color: [0, 0, 0, 160],
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BevelStyle {
+  /** Edges facing the light are highlighted, like a button sticking out. */
+  #[default]
+  Raised,
+  /** Edges facing away from the light are highlighted, like a pressed button or an input field. */
+  Sunken,
+}
+
+/** Highlight and shadow along the inside of the edges of a rect, following the rounded corners. */
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bevel {
+  pub style: BevelStyle,
+  /** Direction the light comes from in degrees, 0 is from the top and 90 from the right. */
+  pub angle: f32,
+  /** Width of the bevel in pixels, measured from the outer edge of the rect. Must be in range [0, 4096]. */
+  pub depth: u16,
+  pub highlight_color: [u8; 4],
+  pub shadow_color: [u8; 4],
+}
+impl Bevel {
+  /**
+  Highlight or shadow for a point `distance` pixels inside of the edge, where `normal` is the direction straight out of the nearest edge.
+
+  Returns None in the flat area past `depth`.
+  */
+  pub(crate) fn color_at(&self, distance: f32, normal: (f32, f32)) -> Option<[u8; 4]> {
+    let strength = (self.depth as f32 - distance + 0.5).clamp(0.0, 1.0);
+    if strength <= 0.0 {
+      return None;
+    }
+    let angle = self.angle.to_radians();
+    let light = (angle.sin(), -angle.cos());
+    let mut facing = normal.0 * light.0 + normal.1 * light.1;
+    if self.style == BevelStyle::Sunken {
+      facing = -facing;
+    }
+    if facing > 0.0 {
+      Some(scale_alpha(&self.highlight_color, facing * strength))
+    } else if facing < 0.0 {
+      Some(scale_alpha(&self.shadow_color, -facing * strength))
+    } else {
+      None
+    }
+  }
+}
+impl Default for Bevel {
+  fn default() -> Self {
+    Bevel {
+      style: BevelStyle::Raised,
+      angle: 315.0,
+      depth: 2,
+      highlight_color: [255, 255, 255, 160],
+      shadow_color: [0, 0, 0, 160],
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::debug::print_matrix;
+
+  use super::*;
+
+  fn square_shape(size: u16, from: u16, to: u16) -> Vec<u8> {
+    let mut shape = vec![0_u8; size as usize * size as usize * 4];
+    for y in from..to {
+      for x in from..to {
+        let i = (y as usize * size as usize + x as usize) * 4;
+        shape[i..i + 4].copy_from_slice(&[255, 0, 0, 255]);
+      }
+    }
+    shape
+  }
+
+  #[test]
+  fn hard_shadow_is_offset_copy() {
+    let size = 8_u16;
+    let shape = square_shape(size, 2, 4);
+    let mut values = vec![0_u8; shape.len()];
+    SpriteorShadowOp {
+      offset: (2, 1),
+      blur_radius: 0,
+      spread: 0,
+      color: [0, 0, 0, 100],
+    }
+    .add_under(&mut values, &shape, &size);
+    print_matrix(&values, size, 3);
+    for y in 0..size as usize {
+      for x in 0..size as usize {
+        let expected = if (4..6).contains(&x) && (3..5).contains(&y) {
+          100
+        } else {
+          0
+        };
+        assert_eq!(values[(y * size as usize + x) * 4 + 3], expected);
+      }
+    }
+  }
+  #[test]
+  fn blurred_shadow_fades_out() {
+    let size = 16_u16;
+    let shape = square_shape(size, 4, 12);
+    let mut values = vec![0_u8; shape.len()];
+    SpriteorShadowOp {
+      offset: (0, 0),
+      blur_radius: 4,
+      spread: 0,
+      color: [0, 0, 0, 255],
+    }
+    .add_under(&mut values, &shape, &size);
+    print_matrix(&values, size, 3);
+    let alpha = |x: usize, y: usize| values[(y * size as usize + x) * 4 + 3];
+    assert!(alpha(8, 8) > 240);
+    assert!(alpha(3, 8) > 0 && alpha(3, 8) < 255);
+    assert!(alpha(2, 8) < alpha(3, 8));
+    assert_eq!(alpha(2, 8), alpha(13, 8));
+  }
+  #[test]
+  fn glow_grows_around_shape() {
+    let size = 8_u16;
+    let shape = square_shape(size, 3, 5);
+    let mut values = vec![0_u8; shape.len()];
+    SpriteorGlowOp {
+      blur_radius: 0,
+      spread: 1,
+      color: [255, 255, 0, 255],
+    }
+    .as_shadow()
+    .add_under(&mut values, &shape, &size);
+    print_matrix(&values, size, 3);
+    let alpha = |x: usize, y: usize| values[(y * size as usize + x) * 4 + 3];
+    assert_eq!(alpha(2, 3), 255);
+    assert_eq!(alpha(5, 4), 255);
+    assert_eq!(alpha(2, 2), 0);
+    assert_eq!(alpha(1, 3), 0);
+  }
+  #[test]
+  fn inner_shadow_darkens_offset_edge() {
+    let (width, height) = (10, 10);
+    let shadow = InnerShadow {
+      offset: (0, 2),
+      blur_radius: 0,
+      color: [0, 0, 0, 255],
+    };
+    let inside = |x: f32, y: f32| {
+      if (0.0..10.0).contains(&x) && (0.0..10.0).contains(&y) {
+        1.0
+      } else {
+        0.0
+      }
+    };
+    let alpha = shadow.alpha_map(width, height, inside);
+    //The top two rows are in the shadow of the top edge, the bottom edge casts nothing.
+    assert!(alpha[..20].iter().all(|a| *a == 1.0));
+    assert!(alpha[20..].iter().all(|a| *a == 0.0));
+  }
+  #[test]
+  fn bevel_lights_edges_facing_the_light() {
+    let bevel = Bevel {
+      angle: 0.0,
+      depth: 2,
+      ..Default::default()
+    };
+    let top = bevel.color_at(0.0, (0.0, -1.0)).unwrap();
+    let bottom = bevel.color_at(0.0, (0.0, 1.0)).unwrap();
+    assert_eq!(top, bevel.highlight_color);
+    assert_eq!(bottom, bevel.shadow_color);
+    assert_eq!(bevel.color_at(0.0, (1.0, 0.0)), None);
+    assert_eq!(bevel.color_at(3.0, (0.0, -1.0)), None);
+
+    let sunken = Bevel {
+      style: BevelStyle::Sunken,
+      ..bevel
+    };
+    assert_eq!(sunken.color_at(0.0, (0.0, -1.0)), Some(sunken.shadow_color));
+  }
+}
@@ -0,0 +1,302 @@
+use std::f32::consts::PI;
+
+use crate::{
+  border_style::StrokeAlign,
+  colors::{add_color_set_pixel, mix_color, scale_alpha},
+  error::{check_coordinate, SpriteorError},
+  maths::xy_to_i,
+  paint::Paint,
+  poly_ops::on_circle,
+  rect_ops::{resolve_area, RectOpUnw},
+};
+
+/** Circle or ellipse filling the area between two points, optionally with a hole in the middle (ring) and cut to an arc or pie slice. */
+#[derive(Debug, Clone)]
+pub struct SpriteorEllipseOp {
+  /**
+  Coordinates (x, y) of the box around the ellipse. Must be in range [-4096, 4096]. Negative values are counted from right-edge for x and bottom for y.
+
+  Works like `point_a` of [`SpriteorRectOp`](crate::SpriteorRectOp), defaults to the whole container.
+  */
+  pub point_a: Option<(i16, i16)>,
+  /** Opposite corner of the box from `point_a`, works like `point_b` of [`SpriteorRectOp`](crate::SpriteorRectOp). */
+  pub point_b: Option<(i16, i16)>,
+  /** Size of the hole in the middle relative to the ellipse in range [0, 1], which turns it into a ring. Defaults to 0, no hole. */
+  pub inner_ratio: f32,
+  /** Degrees clockwise from the top where the arc starts. Defaults to 0. */
+  pub start_angle: f32,
+  /** Degrees clockwise from the top where the arc ends, it always runs clockwise from `start_angle`. 360 or more degrees after the start is the whole ellipse, which is the default. */
+  pub end_angle: f32,
+  /** Width of the border in pixels along the outer and inner edges and the ends of arcs. */
+  pub border_thickness: u8,
+  /** Where the border is drawn relative to the edges. Defaults to inside. */
+  pub stroke_align: StrokeAlign,
+  /** Paint of the border, gradients span the box. Defaults to white. */
+  pub border_color: Option<Paint>,
+  /** Paint inside of the ellipse, gradients span the box. Defaults to light gray. */
+  pub fill_color: Option<Paint>,
+  /** Smooths the edges of the ellipse and its border with partially transparent pixels. Disable for pixel art styles. Defaults to true. */
+  pub anti_alias: bool,
+}
+impl SpriteorEllipseOp {
+  pub(crate) fn validate(&self) -> Result<(), SpriteorError> {
+    for point in [self.point_a, self.point_b].iter().flatten() {
+      check_coordinate(point.0 as i32, point.1 as i32)?;
+    }
+    Ok(())
+  }
+  /** Outline of the ellipse with `radii` in pixels, None if the arc is empty. */
+  fn shape(&self, radii: (f32, f32)) -> Option<EllipseShape> {
+    let span = self.end_angle - self.start_angle;
+    let arc = if span.abs() >= 360.0 {
+      None
+    } else {
+      let turns = span.rem_euclid(360.0) / 360.0;
+      if turns <= 0.0 {
+        return None;
+      }
+      let start = self.start_angle / 360.0;
+      //The ends of the arc are straight lines from the center, stretched with the ellipse.
+      let end = |turn: f32| {
+        let (x, y) = on_circle(turn, 1.0);
+        let (x, y) = (x * radii.0, y * radii.1);
+        let length = x.hypot(y);
+        (x / length, y / length)
+      };
+      Some(EllipseArc {
+        start,
+        turns,
+        ends: [end(start), end(start + turns)],
+      })
+    };
+    Some(EllipseShape {
+      radii,
+      inner_ratio: self.inner_ratio.clamp(0.0, 1.0),
+      arc,
+    })
+  }
+  pub(crate) fn add_to(&self, values: &mut [u8], container: &RectOpUnw, sprite_width: &u16) {
+    let (top, right, bottom, left) = resolve_area(
+      self.point_a,
+      self.point_b,
+      (
+        container.border_box_top,
+        container.border_box_right,
+        container.border_box_bottom,
+        container.border_box_left,
+      ),
+    );
+    let radii = (
+      (right - left + 1) as f32 / 2.0,
+      (bottom - top + 1) as f32 / 2.0,
+    );
+    let center = (left as f32 + radii.0, top as f32 + radii.1);
+    let Some(shape) = self.shape(radii) else {
+      return;
+    };
+    let fill_color = self
+      .fill_color
+      .clone()
+      .unwrap_or(Paint::Solid([200, 200, 200, 255]));
+    let border_color = self
+      .border_color
+      .clone()
+      .unwrap_or(Paint::Solid([255, 255, 255, 255]));
+    let (inside_thickness, outside_thickness) =
+      self.stroke_align.split(self.border_thickness as u16);
+    let thickness = (inside_thickness as f32, outside_thickness as f32);
+
+    //Outside borders and smoothed edges reach out of the box.
+    let reach = outside_thickness as i32 + 1;
+    let sprite_height = (values.len() / 4 / *sprite_width as usize) as i32;
+    let columns =
+      (left as i32 - reach).max(0)..(right as i32 + reach + 1).min(*sprite_width as i32);
+    for y in (top as i32 - reach).max(0)..(bottom as i32 + reach + 1).min(sprite_height) {
+      for x in columns.clone() {
+        let distance = shape.distance((x as f32 + 0.5 - center.0, y as f32 + 0.5 - center.1));
+        let Some((coverage, fill)) = self.coverage(distance, thickness) else {
+          continue;
+        };
+        let (x, y) = (x as u16, y as u16);
+        let paint_at = |paint: &Paint| paint.color_at(&x, &y, &left, &top, &right, &bottom);
+        let color = if fill >= 1.0 {
+          paint_at(&fill_color)
+        } else if fill <= 0.0 {
+          paint_at(&border_color)
+        } else {
+          mix_color(&paint_at(&border_color), &paint_at(&fill_color), fill)
+        };
+        add_color_set_pixel(
+          values,
+          &xy_to_i(sprite_width, &x, &y),
+          &scale_alpha(&color, coverage),
+        );
+      }
+    }
+  }
+
+  /**
+  How much of a pixel is covered by the ellipse and its border and how much of that is fill rather than border, both in range [0, 1]. None if the pixel is not covered at all.
+
+  `distance` is the signed distance from the pixel center to the edge, `thickness` the border (inside, outside) of the edge. Without anti-aliasing coverage is always 1 and fill either 0 or 1.
+  */
+  fn coverage(&self, distance: f32, thickness: (f32, f32)) -> Option<(f32, f32)> {
+    let (inside_thickness, outside_thickness) = thickness;
+    if !self.anti_alias {
+      return if distance < 0.0 {
+        let is_border = -distance < inside_thickness;
+        Some((1.0, if is_border { 0.0 } else { 1.0 }))
+      } else if distance < outside_thickness {
+        Some((1.0, 0.0))
+      } else {
+        None
+      };
+    }
+    let coverage = (0.5 - distance + outside_thickness).clamp(0.0, 1.0);
+    if coverage <= 0.0 {
+      return None;
+    }
+    if self.border_thickness == 0 {
+      return Some((coverage, 1.0));
+    }
+    let fill = (0.5 - distance - inside_thickness).clamp(0.0, coverage);
+    Some((coverage, fill / coverage))
+  }
+}
+/** Ellipse, ring or arc in pixels around the origin. */
+struct EllipseShape {
+  radii: (f32, f32),
+  inner_ratio: f32,
+  /** None for whole ellipses. */
+  arc: Option<EllipseArc>,
+}
+/** Part of an ellipse between two straight ends from the center. */
+struct EllipseArc {
+  /** Turn clockwise from the top where the arc starts. */
+  start: f32,
+  /** Length in turns, in range (0, 1). */
+  turns: f32,
+  /** Directions of the start and end from the center, with a length of 1. */
+  ends: [(f32, f32); 2],
+}
+impl EllipseShape {
+  /** Signed distance from `point` to the edge of the shape, negative inside. Corners of arcs are sharp. */
+  fn distance(&self, point: (f32, f32)) -> f32 {
+    let mut distance = ellipse_distance(point, self.radii);
+    if self.inner_ratio > 0.0 {
+      let hole = (
+        self.radii.0 * self.inner_ratio,
+        self.radii.1 * self.inner_ratio,
+      );
+      distance = distance.max(-ellipse_distance(point, hole));
+    }
+    if let Some(arc) = &self.arc {
+      let turn = (point.0 / self.radii.0).atan2(-point.1 / self.radii.1) / (2.0 * PI);
+      let to_ends = ray_distance(point, arc.ends[0]).min(ray_distance(point, arc.ends[1]));
+      distance = distance.max(if (turn - arc.start).rem_euclid(1.0) <= arc.turns {
+        -to_ends
+      } else {
+        to_ends
+      });
+    }
+    distance
+  }
+}
+
+/** Signed distance from `point` to an ellipse with `radii` around the origin, negative inside. Exact for circles and close to the edge of other ellipses. */
+fn ellipse_distance(point: (f32, f32), radii: (f32, f32)) -> f32 {
+  let scaled = (point.0 / radii.0, point.1 / radii.1);
+  let length = scaled.0.hypot(scaled.1);
+  //The scaled length grows this fast moving away from the edge.
+  let gradient = (scaled.0 / radii.0).hypot(scaled.1 / radii.1);
+  if gradient <= f32::EPSILON {
+    return -radii.0.min(radii.1);
+  }
+  (length - 1.0) * length / gradient
+}
+
+/** Distance from `point` to the ray from the origin in `direction`, which has a length of 1. */
+fn ray_distance(point: (f32, f32), direction: (f32, f32)) -> f32 {
+  let along = point.0 * direction.0 + point.1 * direction.1;
+  if along <= 0.0 {
+    point.0.hypot(point.1)
+  } else {
+    (point.0 * direction.1 - point.1 * direction.0).abs()
+  }
+}
+impl Default for SpriteorEllipseOp {
+  fn default() -> Self {
+    SpriteorEllipseOp {
+      point_a: None,
+      point_b: None,
+      inner_ratio: 0.0,
+      start_angle: 0.0,
+      end_angle: 360.0,
+      border_thickness: 0,
+      stroke_align: StrokeAlign::Inside,
+      border_color: None,
+      fill_color: None,
+      anti_alias: true,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn circle_distance_is_exact() {
+    let circle = SpriteorEllipseOp::default().shape((8.0, 8.0)).unwrap();
+    assert_eq!(circle.distance((0.0, 0.0)), -8.0);
+    assert!((circle.distance((3.0, 4.0)) + 3.0).abs() < 1e-4);
+    assert!((circle.distance((-6.0, 8.0)) - 2.0).abs() < 1e-4);
+    //Ellipses are exact at the ends of their axes.
+    let ellipse = SpriteorEllipseOp::default().shape((8.0, 4.0)).unwrap();
+    assert!((ellipse.distance((10.0, 0.0)) - 2.0).abs() < 1e-4);
+    assert!((ellipse.distance((0.0, -3.0)) + 1.0).abs() < 1e-4);
+  }
+  #[test]
+  fn rings_have_a_hole() {
+    let ring = SpriteorEllipseOp {
+      inner_ratio: 0.5,
+      ..Default::default()
+    }
+    .shape((8.0, 8.0))
+    .unwrap();
+    assert!((ring.distance((0.0, 0.0)) - 4.0).abs() < 1e-4);
+    assert!((ring.distance((0.0, 6.0)) + 2.0).abs() < 1e-4);
+    assert!((ring.distance((0.0, 9.0)) - 1.0).abs() < 1e-4);
+  }
+  #[test]
+  fn arcs_run_clockwise() {
+    let pie = SpriteorEllipseOp {
+      start_angle: 90.0,
+      end_angle: 180.0,
+      ..Default::default()
+    }
+    .shape((8.0, 8.0))
+    .unwrap();
+    //The bottom right quarter, measured to the straight ends.
+    assert!((pie.distance((4.0, 2.0)) + 2.0).abs() < 1e-4);
+    assert!((pie.distance((-3.0, 4.0)) - 3.0).abs() < 1e-4);
+    assert!((pie.distance((-3.0, -4.0)) - 5.0).abs() < 1e-4);
+    //Ending before the start wraps around through the top.
+    let arc = SpriteorEllipseOp {
+      inner_ratio: 0.5,
+      start_angle: 270.0,
+      end_angle: 90.0,
+      ..Default::default()
+    }
+    .shape((8.0, 8.0))
+    .unwrap();
+    assert!(arc.distance((0.0, -6.0)) < 0.0);
+    assert!(arc.distance((0.0, 6.0)) > 0.0);
+    let empty = SpriteorEllipseOp {
+      start_angle: 45.0,
+      end_angle: 45.0,
+      ..Default::default()
+    };
+    assert!(empty.shape((8.0, 8.0)).is_none());
+  }
+}
@@ -0,0 +1,135 @@
+use std::{error::Error, fmt};
+
+/** Min and max allowed for sprite width/height. */
+pub(crate) const SIZE_RANGE: (u16, u16) = (8, 4096);
+/** Min and max allowed for coordinates and offsets given to ops. */
+pub(crate) const COORDINATE_RANGE: (i32, i32) = (-4096, 4096);
+/** Min and max allowed for widths, lengths and radii given to ops. */
+pub(crate) const LENGTH_RANGE: (i32, i32) = (0, 4096);
+/** Max allowed for blur radii and spreads of effects, which get slow on large sprites well before the other lengths. */
+pub(crate) const EFFECT_MAX: i32 = 256;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpriteorError {
+  /** Width or height outside of [8, 4096]. */
+  InvalidSize { width: u16, height: u16 },
+  /** Border of a side is wider than the box it is drawn on. `width` and `height` are the size of the resolved box in pixels. */
+  BorderLargerThanBox {
+    border_width: u16,
+    width: u16,
+    height: u16,
+  },
+  /** A `SpriteorPolyOp` with `x_count` or `y_count` set to 0. */
+  ZeroTileCount { x_count: u16, y_count: u16 },
+  /** A line op with a width of 0. */
+  ZeroLineWidth,
+  /** A point or offset outside of [-4096, 4096]. */
+  CoordinateOutOfRange { x: i32, y: i32 },
+  /** A width, length or radius outside of the range in its docs, `name` is the field it was given in. */
+  ValueOutOfRange {
+    name: &'static str,
+    value: i32,
+    min: i32,
+    max: i32,
+  },
+  /** RGBA values given to [`crate::encode_png`] that are not `width * height * 4` long, `count` is their length. */
+  InvalidValueCount {
+    width: u16,
+    height: u16,
+    count: usize,
+  },
+  /** SVG path data that could not be parsed, `index` is the byte offset of the first character that does not fit. */
+  InvalidPathData { index: usize },
+  /** A `view_box` given to [`crate::SpriteorPathOp::from_svg`] without a finite, positive width and height. */
+  InvalidViewBox,
+}
+
+impl fmt::Display for SpriteorError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      SpriteorError::InvalidSize { width, height } => write!(
+        f,
+        "Invalid sizing {}x{}, width and height must be in range [{}, {}].",
+        width, height, SIZE_RANGE.0, SIZE_RANGE.1
+      ),
+      SpriteorError::BorderLargerThanBox {
+        border_width,
+        width,
+        height,
+      } => write!(
+        f,
+        "Border width {} does not fit inside a {}x{} box.",
+        border_width, width, height
+      ),
+      SpriteorError::ZeroTileCount { x_count, y_count } => write!(
+        f,
+        "Invalid tile count {}x{}, x_count and y_count must be at least 1.",
+        x_count, y_count
+      ),
+      SpriteorError::ZeroLineWidth => write!(f, "Line width must be at least 1."),
+      SpriteorError::CoordinateOutOfRange { x, y } => write!(
+        f,
+        "Coordinate ({}, {}) out of range, must be in range [{}, {}].",
+        x, y, COORDINATE_RANGE.0, COORDINATE_RANGE.1
+      ),
+      SpriteorError::ValueOutOfRange {
+        name,
+        value,
+        min,
+        max,
+      } => write!(
+        f,
+        "Invalid {} {}, must be in range [{}, {}].",
+        name, value, min, max
+      ),
+      SpriteorError::InvalidValueCount {
+        width,
+        height,
+        count,
+      } => write!(
+        f,
+        "Invalid value count {}, expected {} for {}x{} pixels.",
+        count,
+        *width as usize * *height as usize * 4,
+        width,
+        height
+      ),
+      SpriteorError::InvalidPathData { index } => {
+        write!(f, "Invalid SVG path data at byte {}.", index)
+      }
+      SpriteorError::InvalidViewBox => {
+        write!(f, "View box must have a finite, positive width and height.")
+      }
+    }
+  }
+}
+
+impl Error for SpriteorError {}
+
+/** Returns an error if x or y is outside of `COORDINATE_RANGE`. */
+pub(crate) fn check_coordinate(x: i32, y: i32) -> Result<(), SpriteorError> {
+  let range = COORDINATE_RANGE.0..=COORDINATE_RANGE.1;
+  if range.contains(&x) && range.contains(&y) {
+    Ok(())
+  } else {
+    Err(SpriteorError::CoordinateOutOfRange { x, y })
+  }
+}
+
+/** Returns an error if `value` is outside of `range`, `name` is the field it was given in. */
+pub(crate) fn check_range(
+  name: &'static str,
+  value: i32,
+  range: (i32, i32),
+) -> Result<(), SpriteorError> {
+  if (range.0..=range.1).contains(&value) {
+    Ok(())
+  } else {
+    Err(SpriteorError::ValueOutOfRange {
+      name,
+      value,
+      min: range.0,
+      max: range.1,
+    })
+  }
+}
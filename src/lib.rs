@@ -1,8 +1,48 @@
+mod blur;
+mod border_style;
 mod colors;
 mod debug;
+mod effect_ops;
+mod ellipse_ops;
+mod error;
 mod line_ops;
 mod maths;
+mod paint;
+mod path_ops;
 mod patterns;
+mod png;
 mod poly_ops;
+mod raster;
 mod rect_ops;
 pub mod spriteor;
+mod svg_path;
+
+pub use border_style::{BorderStyle, StrokeAlign, StrokeCap, StrokeJoin};
+pub use effect_ops::{Bevel, BevelStyle, InnerShadow, SpriteorGlowOp, SpriteorShadowOp};
+pub use ellipse_ops::SpriteorEllipseOp;
+pub use error::SpriteorError;
+pub use line_ops::{SpriteorHLineOp, SpriteorLineOp, SpriteorVLineOp};
+pub use paint::Paint;
+pub use path_ops::{PathCommand, SpriteorPathOp};
+pub use png::{encode_png, PngOptions, SrgbIntent};
+pub use poly_ops::{
+  regular_polygon, rounded_polygon, star_polygon, PolyFit, PolyTransform, SpriteorPolyOp,
+  TileAlternation, TileStagger, CROSS_POLY, DIAMOND_POLY, FIVESTAR_POLY, FOURSTAR_POLY,
+  HEXAGON_POLY, OCTAGON_POLY, PENTAGON_POLY, SQUARE_POLY, TRIANGLE_POLY,
+};
+pub use raster::FillRule;
+pub use rect_ops::{CornerStyle, SpriteorRectOp};
+pub use spriteor::{Spriteor, SpriteorOperation, SpriteorSettings, SpriteorSettingsBuilder};
+
+/** Glob import of everything needed to build and finalize a sprite: `use ui_spriteor::prelude::*;` */
+pub mod prelude {
+  pub use crate::{
+    regular_polygon, rounded_polygon, star_polygon, Bevel, BevelStyle, BorderStyle, CornerStyle,
+    FillRule, InnerShadow, Paint, PathCommand, PngOptions, PolyFit, PolyTransform, Spriteor,
+    SpriteorEllipseOp, SpriteorError, SpriteorGlowOp, SpriteorHLineOp, SpriteorLineOp,
+    SpriteorOperation, SpriteorPathOp, SpriteorPolyOp, SpriteorRectOp, SpriteorSettings,
+    SpriteorSettingsBuilder, SpriteorShadowOp, SpriteorVLineOp, SrgbIntent, StrokeAlign, StrokeCap,
+    StrokeJoin, TileAlternation, TileStagger, CROSS_POLY, DIAMOND_POLY, FIVESTAR_POLY,
+    FOURSTAR_POLY, HEXAGON_POLY, OCTAGON_POLY, PENTAGON_POLY, SQUARE_POLY, TRIANGLE_POLY,
+  };
+}
@@ -1,23 +1,53 @@
-use crate::rect_ops::RectOpUnw;
+use crate::{
+  border_style::{BorderStyle, StrokeCap, StrokeJoin},
+  colors::scale_alpha,
+  error::{check_coordinate, check_range, SpriteorError, LENGTH_RANGE},
+  maths::{distance_to_polyline, MITRE_LIMIT},
+  paint::Paint,
+  rect_ops::{resolve_area, resolve_coordinate, RectOpUnw},
+};
 
 #[derive(Debug, Clone)]
 pub struct SpriteorVLineOp {
-  /** Width of line, must be in range [1-4096]. */
+  /** Width of line, must be in range [1, 4096]. */
   pub width: u16,
-  /** X position from the left edge. */
-  pub x: u16,
-  pub color: Option<[u8; 4]>,
+  /** X position, resolved and clamped to the container like the x of `point_a` of [`SpriteorRectOp`](crate::SpriteorRectOp). Negative values are counted from the right edge, the line then ends there. */
+  pub x: i16,
+  /** Y where the line starts, resolved like `y`. Defaults to the top edge. */
+  pub start: Option<i16>,
+  /** Y where the line ends including that pixel, resolved like `y`. Defaults to the bottom edge. */
+  pub end: Option<i16>,
+  /** Measures `x` from the center of the container instead, so 0 centers the line and negative values move it left. */
+  pub centered: bool,
+  /** Pattern of the line, for example `Dashed` or `Dotted`. Fitted to the length like the borders of [`SpriteorRectOp`](crate::SpriteorRectOp) so both ends look the same. Defaults to solid. */
+  pub style: BorderStyle,
+  /** Paint of the line, gradients span the length and width of the line. Defaults to white. */
+  pub color: Option<Paint>,
 }
 impl SpriteorVLineOp {
-  pub(crate) fn add_to(&self, values: &mut Vec<u8>, container: &RectOpUnw, sprite_width: &u16) {
-    let rect = RectOpUnw::new(
-      0,
-      (container.border_box_left + self.x + self.width - 1).min(container.border_box_right),
-      container.border_box_bottom,
-      (container.border_box_left + self.x).min(container.border_box_right),
-      self.color.unwrap_or([255, 255, 255, 255]),
-    );
-    rect.add_to(values, container, sprite_width);
+  pub(crate) fn validate(&self) -> Result<(), SpriteorError> {
+    if self.width == 0 {
+      return Err(SpriteorError::ZeroLineWidth);
+    }
+    check_range("width", self.width as i32, (1, LENGTH_RANGE.1))?;
+    check_coordinate(self.x as i32, 0)?;
+    self.style.validate()?;
+    for y in [self.start, self.end].iter().flatten() {
+      check_coordinate(0, *y as i32)?;
+    }
+    Ok(())
+  }
+  pub(crate) fn add_to(&self, values: &mut [u8], container: &RectOpUnw, sprite_width: &u16) {
+    AxisLine {
+      vertical: true,
+      offset: self.x,
+      start: self.start,
+      end: self.end,
+      centered: self.centered,
+      width: self.width,
+      style: self.style,
+    }
+    .add_to(values, container, sprite_width, &self.color);
   }
 }
 impl Default for SpriteorVLineOp {
@@ -25,6 +55,10 @@ impl Default for SpriteorVLineOp {
     SpriteorVLineOp {
       width: 1,
       x: 0,
+      start: None,
+      end: None,
+      centered: false,
+      style: BorderStyle::Solid,
       color: None,
     }
   }
@@ -34,20 +68,43 @@ impl Default for SpriteorVLineOp {
 pub struct SpriteorHLineOp {
   /** Width of line, must be in range [1, 4096]. */
   pub width: u16,
-  /** Y position from the top edge. */
-  pub y: u16,
-  pub color: Option<[u8; 4]>,
+  /** Y position, resolved and clamped to the container like the y of `point_a` of [`SpriteorRectOp`](crate::SpriteorRectOp). Negative values are counted from the bottom edge, the line then ends there. */
+  pub y: i16,
+  /** X where the line starts, resolved like `x`. Defaults to the left edge. */
+  pub start: Option<i16>,
+  /** X where the line ends including that pixel, resolved like `x`. Defaults to the right edge. */
+  pub end: Option<i16>,
+  /** Measures `y` from the center of the container instead, so 0 centers the line and negative values move it up. */
+  pub centered: bool,
+  /** Pattern of the line, for example `Dashed` or `Dotted`. Fitted to the length like the borders of [`SpriteorRectOp`](crate::SpriteorRectOp) so both ends look the same. Defaults to solid. */
+  pub style: BorderStyle,
+  /** Paint of the line, gradients span the length and width of the line. Defaults to white. */
+  pub color: Option<Paint>,
 }
 impl SpriteorHLineOp {
-  pub(crate) fn add_to(&self, values: &mut Vec<u8>, container: &RectOpUnw, sprite_width: &u16) {
-    let rect = RectOpUnw::new(
-      (container.border_box_top + self.y).min(container.border_box_bottom),
-      container.border_box_right,
-      (container.border_box_top + self.y + self.width - 1).min(container.border_box_right),
-      0,
-      self.color.unwrap_or([255, 255, 255, 255]),
-    );
-    rect.add_to(values, container, sprite_width);
+  pub(crate) fn validate(&self) -> Result<(), SpriteorError> {
+    if self.width == 0 {
+      return Err(SpriteorError::ZeroLineWidth);
+    }
+    check_range("width", self.width as i32, (1, LENGTH_RANGE.1))?;
+    check_coordinate(0, self.y as i32)?;
+    self.style.validate()?;
+    for x in [self.start, self.end].iter().flatten() {
+      check_coordinate(*x as i32, 0)?;
+    }
+    Ok(())
+  }
+  pub(crate) fn add_to(&self, values: &mut [u8], container: &RectOpUnw, sprite_width: &u16) {
+    AxisLine {
+      vertical: false,
+      offset: self.y,
+      start: self.start,
+      end: self.end,
+      centered: self.centered,
+      width: self.width,
+      style: self.style,
+    }
+    .add_to(values, container, sprite_width, &self.color);
   }
 }
 impl Default for SpriteorHLineOp {
@@ -55,7 +112,214 @@ impl Default for SpriteorHLineOp {
     SpriteorHLineOp {
       width: 1,
       y: 0,
+      start: None,
+      end: None,
+      centered: false,
+      style: BorderStyle::Solid,
+      color: None,
+    }
+  }
+}
+
+/** Horizontal or vertical line of the H and V line ops, `offset` is across the line and `start` and `end` along it. */
+struct AxisLine {
+  vertical: bool,
+  offset: i16,
+  start: Option<i16>,
+  end: Option<i16>,
+  centered: bool,
+  width: u16,
+  style: BorderStyle,
+}
+impl AxisLine {
+  fn add_to(
+    &self,
+    values: &mut [u8],
+    container: &RectOpUnw,
+    sprite_width: &u16,
+    color: &Option<Paint>,
+  ) {
+    let horizontal = (
+      container.border_box_left as i32,
+      container.border_box_right as i32,
+    );
+    let vertical = (
+      container.border_box_top as i32,
+      container.border_box_bottom as i32,
+    );
+    let (along, across) = if self.vertical {
+      (vertical, horizontal)
+    } else {
+      (horizontal, vertical)
+    };
+    //Positions are resolved like the points of the other ops and clamped to the container.
+    let resolve = |value: i16, (first, last): (i32, i32)| {
+      resolve_coordinate(value, first as u16, last as u16) as i32
+    };
+    let width = self.width as i32;
+    let across_start = if self.centered {
+      (across.0 + (across.1 - across.0 + 1 - width) / 2 + self.offset as i32)
+        .clamp(across.0, across.1)
+    } else if self.offset < 0 {
+      (resolve(self.offset, across) - width + 1).max(across.0)
+    } else {
+      resolve(self.offset, across)
+    };
+    let across_end = (across_start + width - 1).min(across.1);
+    let start = self.start.map_or(along.0, |start| resolve(start, along));
+    let end = self.end.map_or(along.1, |end| resolve(end, along));
+    let (start, end) = (start.min(end), start.max(end));
+
+    let color = color.clone().unwrap_or(Paint::Solid([255, 255, 255, 255]));
+    let (left, top, right, bottom) = if self.vertical {
+      (across_start, start, across_end, end)
+    } else {
+      (start, across_start, end, across_end)
+    };
+    let bounds = (left as u16, top as u16, right as u16, bottom as u16);
+    let sprite_height = (values.len() / 4 / *sprite_width as usize) as i32;
+    for position in start..=end {
+      for depth in across_start..=across_end {
+        let (x, y) = if self.vertical {
+          (depth, position)
+        } else {
+          (position, depth)
+        };
+        if x >= *sprite_width as i32 || y >= sprite_height {
+          continue;
+        }
+        let (x, y) = (x as u16, y as u16);
+        let paint = color.color_at(&x, &y, &bounds.0, &bounds.1, &bounds.2, &bounds.3);
+        let styled = self.style.color_at(
+          &paint,
+          (position - start) as f32,
+          (end - start) as f32,
+          ((depth - across_start) as f32 + 0.5) / width as f32,
+          width as f32,
+          true,
+        );
+        //Clipped by the rounded or styled corners of the container like everything else drawn in it.
+        if let Some(styled) = styled {
+          container.add_to_pixel_if_inside(values, sprite_width, &x, &y, &styled);
+        }
+      }
+    }
+  }
+}
+
+/** Straight line or polyline at any angle, drawn inside of the current container. */
+#[derive(Debug, Clone)]
+pub struct SpriteorLineOp {
+  /**
+  Pixels (x, y) the line runs through the centers of, in order. Must be in range [-4096, 4096]. Negative values are counted from right-edge for x and bottom for y.
+
+  Points are clamped to the container like `point_a` of [`SpriteorRectOp`](crate::SpriteorRectOp). A single point is drawn as a dot with round and square caps.
+  */
+  pub points: Vec<(i16, i16)>,
+  /** Width of line, must be in range [1, 4096]. */
+  pub width: u16,
+  /** Shape of the two ends of the line. Defaults to square, so the end points are fully covered like with the other line ops. */
+  pub cap: StrokeCap,
+  /** Shape of the corners where the segments of a polyline meet. Defaults to mitre. */
+  pub join: StrokeJoin,
+  /** Paint of the line, gradients span the box around the points. Defaults to white. */
+  pub color: Option<Paint>,
+  /** Smooths the edges of the line with partially transparent pixels. Disable for pixel art styles. Defaults to true. */
+  pub anti_alias: bool,
+}
+impl SpriteorLineOp {
+  pub(crate) fn validate(&self) -> Result<(), SpriteorError> {
+    if self.width == 0 {
+      return Err(SpriteorError::ZeroLineWidth);
+    }
+    check_range("width", self.width as i32, (1, LENGTH_RANGE.1))?;
+    for point in &self.points {
+      check_coordinate(point.0 as i32, point.1 as i32)?;
+    }
+    Ok(())
+  }
+  pub(crate) fn add_to(&self, values: &mut [u8], container: &RectOpUnw, sprite_width: &u16) {
+    let parent = (
+      container.border_box_top,
+      container.border_box_right,
+      container.border_box_bottom,
+      container.border_box_left,
+    );
+    let pixels: Vec<(u16, u16)> = self
+      .points
+      .iter()
+      .map(|point| {
+        let (top, _, _, left) = resolve_area(Some(*point), Some(*point), parent);
+        (left, top)
+      })
+      .collect();
+    let (Some(left), Some(right), Some(top), Some(bottom)) = (
+      pixels.iter().map(|p| p.0).min(),
+      pixels.iter().map(|p| p.0).max(),
+      pixels.iter().map(|p| p.1).min(),
+      pixels.iter().map(|p| p.1).max(),
+    ) else {
+      return;
+    };
+    let centers: Vec<(f32, f32)> = pixels
+      .iter()
+      .map(|p| (p.0 as f32 + 0.5, p.1 as f32 + 0.5))
+      .collect();
+    let color = self
+      .color
+      .clone()
+      .unwrap_or(Paint::Solid([255, 255, 255, 255]));
+
+    //Mitres reach furthest from the points, everything past them is left out.
+    let half_width = self.width as f32 / 2.0;
+    let reach = (half_width * MITRE_LIMIT).ceil() as i32 + 1;
+    let sprite_height = (values.len() / 4 / *sprite_width as usize) as i32;
+    let y_range = (top as i32 - reach).max(container.border_box_top as i32)
+      ..=(bottom as i32 + reach)
+        .min(container.border_box_bottom as i32)
+        .min(sprite_height - 1);
+    let x_range = (left as i32 - reach).max(container.border_box_left as i32)
+      ..=(right as i32 + reach)
+        .min(container.border_box_right as i32)
+        .min(*sprite_width as i32 - 1);
+    for y in y_range {
+      for x in x_range.clone() {
+        let (x, y) = (x as u16, y as u16);
+        let center = (x as f32 + 0.5, y as f32 + 0.5);
+        let distance = distance_to_polyline(&centers, &center, half_width, self.join, self.cap);
+        let coverage = if self.anti_alias {
+          (half_width + 0.5 - distance).clamp(0.0, 1.0)
+        } else if distance < half_width {
+          1.0
+        } else {
+          0.0
+        };
+        if coverage <= 0.0 {
+          continue;
+        }
+        container.add_to_pixel_if_inside(
+          values,
+          sprite_width,
+          &x,
+          &y,
+          &scale_alpha(
+            &color.color_at(&x, &y, &left, &top, &right, &bottom),
+            coverage,
+          ),
+        );
+      }
+    }
+  }
+}
+impl Default for SpriteorLineOp {
+  fn default() -> Self {
+    SpriteorLineOp {
+      points: Vec::new(),
+      width: 1,
+      cap: StrokeCap::Square,
+      join: StrokeJoin::Mitre,
       color: None,
+      anti_alias: true,
     }
   }
 }
@@ -1,7 +1,9 @@
 use std::ops::{Add, Div, Mul, Sub};
 
+use crate::border_style::{StrokeCap, StrokeJoin};
+
 pub(crate) fn xy_to_i(width: &u16, x: &u16, y: &u16) -> usize {
-  (y * width + x) as usize
+  *y as usize * *width as usize + *x as usize
 }
 
 /** Converts u16 to f32 and calculates planar distance between a and b. */
@@ -24,195 +26,214 @@ fn distance_to_segment(a: &(f32, f32), b: &(f32, f32), p: &(f32, f32)) -> f32 {
     return distance_f32(a.0, a.1, p.0, p.1);
   }
 
-  let t = ((p.0 - a.0) * (b.0 - a.0) + (p.1 - a.1) * (b.1 - a.1)) as f32 / length;
-  let t_clamped = 0.0_f32.max(1.0_f32.min(t));
-  let x = (
-    a.0 as f32 + t_clamped * (b.0 - a.0) as f32,
-    a.1 as f32 + t_clamped * (b.1 - a.1) as f32,
-  );
-  distance_f32(p.0 as f32, p.1 as f32, x.0, x.1)
+  let t = ((p.0 - a.0) * (b.0 - a.0) + (p.1 - a.1) * (b.1 - a.1)) / length;
+  let t_clamped = t.clamp(0.0, 1.0);
+  let x = (a.0 + t_clamped * (b.0 - a.0), a.1 + t_clamped * (b.1 - a.1));
+  distance_f32(p.0, p.1, x.0, x.1)
 }
 
-#[derive(PartialEq, Debug)]
-pub(crate) enum ContainsResult {
-  Inside,
-  Outside,
-  Border,
+/** Mitre length relative to the border width past which mitre joins are beveled, the SVG default. */
+pub(crate) const MITRE_LIMIT: f32 = 4.0;
+
+/** Largest angle in radians between points on an arc of `radius` pixels whose chords stay within `tolerance` pixels of it. */
+pub(crate) fn arc_step(radius: f32, tolerance: f32) -> f32 {
+  2.0 * (1.0 - tolerance / radius).clamp(-1.0, 1.0).acos()
 }
 
-pub(crate) fn path_intersection(
-  a1: &(f32, f32),
-  a2: &(f32, f32),
-  b1: &(f32, f32),
-  b2: &(f32, f32),
-) -> Option<(f32, f32)> {
-  //https://pastebin.com/nf56MHP7
-  let s1x = a2.0 - a1.0;
-  let s1y = a2.1 - a1.1;
-  let s2x = b2.0 - b1.0;
-  let s2y = b2.1 - b1.1;
+/**
+Distance from p to the outline of a closed polygon, with the corners shaped by `join`.
 
-  let sdiv = -s2x * s1y + s1x * s2y;
-  let tdiv = -s2x * s1y + s1x * s2y;
+Points whose closest point is a vertex on the outer side of a corner measure to the mitred or beveled corner instead of to the vertex, so every distance describes the same shape a stroke with that join would have.
+*/
+pub(crate) fn distance_to_outline(polygon: &[(f32, f32)], p: &(f32, f32), join: StrokeJoin) -> f32 {
+  (0..polygon.len())
+    .map(|i| outline_piece_distance(polygon, i, p, join))
+    .fold(f32::MAX, f32::min)
+}
 
-  if sdiv == 0.0 || tdiv == 0.0 {
-    return None;
+/** Distance from p to edge `i` of a closed polygon, from point `i` to the next one, and to the corner at its start. The smallest of these over all edges is [`distance_to_outline`]. */
+pub(crate) fn outline_piece_distance(
+  polygon: &[(f32, f32)],
+  i: usize,
+  p: &(f32, f32),
+  join: StrokeJoin,
+) -> f32 {
+  let len = polygon.len();
+  let (a, b) = (&polygon[i], &polygon[(i + 1) % len]);
+  let mut distance = f32::MAX;
+  //Only the straight part of the edge, the ends are measured at the vertices.
+  let length = distance_no_sqrt(a, b);
+  if length > 0.0 {
+    let t = ((p.0 - a.0) * (b.0 - a.0) + (p.1 - a.1) * (b.1 - a.1)) / length;
+    if (0.0..=1.0).contains(&t) {
+      distance = distance_to_segment(a, b, p);
+    }
   }
-  let s = (-s1y * (a1.0 - b1.0) + s1x * (a1.1 - b1.1)) / sdiv;
-  let t = (s2x * (a1.1 - b1.1) - s2y * (a1.0 - b1.0)) / sdiv;
+  let previous = &polygon[(i + len - 1) % len];
+  distance.min(corner_distance(previous, a, b, p, join))
+}
 
-  if s >= 0.0 && s <= 1.0 && t >= 0.0 && t <= 1.0 {
-    return Some((a1.0 + (t * s1x), a1.1 + (t * s1y)));
+/**
+Distance from p to an open polyline through `points`, with the corners shaped by `join` and the ends by `cap`.
+
+A stroke with half width `half_width` covers the points closer than it. Butt caps are measured so the stroke ends exactly at the end points, a single point is a dot with round and square caps.
+*/
+pub(crate) fn distance_to_polyline(
+  points: &[(f32, f32)],
+  p: &(f32, f32),
+  half_width: f32,
+  join: StrokeJoin,
+  cap: StrokeCap,
+) -> f32 {
+  let mut points = points.to_vec();
+  points.dedup();
+  if let [point] = points[..] {
+    return dot_distance(&point, p, cap);
   }
-  None
+  (0..points.len().saturating_sub(1))
+    .map(|i| polyline_piece_distance(&points, i, p, half_width, join, cap))
+    .fold(f32::MAX, f32::min)
 }
 
-pub(crate) fn poly_contains(
-  polygon: &Vec<(f32, f32)>,
-  p: &(u16, u16),
-  border_thickness: u8,
-) -> ContainsResult {
-  let target = (p.0 as f32, p.1 as f32);
-  if target.0 == polygon[0].0 && target.1 == polygon[0].1 {
-    //Point is on the edge, counts as inside.
-    if border_thickness > 0 {
-      return ContainsResult::Border;
-    }
-    return ContainsResult::Inside;
+/** Distance from p to a polyline of a single `point`, which is a dot with round and square caps. */
+pub(crate) fn dot_distance(point: &(f32, f32), p: &(f32, f32), cap: StrokeCap) -> f32 {
+  let (dx, dy) = ((p.0 - point.0).abs(), (p.1 - point.1).abs());
+  match cap {
+    StrokeCap::Butt => f32::MAX,
+    StrokeCap::Round => dx.hypot(dy),
+    StrokeCap::Square => dx.max(dy),
   }
-  let origin = (-1.0, -1.0);
+}
 
-  let mut intersections: usize = 0;
-  let mut poly_point_intersections: usize = 0;
-  let mut last_intersection_seg_index: usize = 0;
+/** Distance from p to segment `i` of a polyline without repeated points, from point `i` to the next one, and to the corner at its start. The smallest of these over all segments is [`distance_to_polyline`]. */
+pub(crate) fn polyline_piece_distance(
+  points: &[(f32, f32)],
+  i: usize,
+  p: &(f32, f32),
+  half_width: f32,
+  join: StrokeJoin,
+  cap: StrokeCap,
+) -> f32 {
+  let (a, b) = (&points[i], &points[i + 1]);
+  let last = points.len().saturating_sub(2);
+  let mut distance = f32::MAX;
+  if let Some(direction) = unit(a, b) {
+    let length = distance_f32(a.0, a.1, b.0, b.1);
+    let along = (p.0 - a.0) * direction.0 + (p.1 - a.1) * direction.1;
+    distance = ((p.1 - a.1) * direction.0 - (p.0 - a.0) * direction.1).abs();
+    //How far p is past the start and past the end, negative when it is next to the segment.
+    for (past, is_end) in [(-along, i == 0), (along - length, i == last)] {
+      distance = match (is_end, cap) {
+        (false, _) if past > 0.0 => f32::MAX,
+        (false, _) => distance,
+        (true, StrokeCap::Butt) => distance.max(past + half_width),
+        (true, StrokeCap::Square) => distance.max(past),
+        (true, StrokeCap::Round) if past > 0.0 => distance.hypot(past),
+        (true, StrokeCap::Round) => distance,
+      };
+    }
+  }
+  if i > 0 {
+    distance = distance.min(corner_distance(&points[i - 1], a, b, p, join));
+  }
+  distance
+}
 
-  let mut seg_point_a = polygon[0];
+/** How far from `vertex` a stroke with `half_width` reaches at the corner, which is the length of the mitre for mitre joins that are not beveled. */
+pub(crate) fn corner_reach(
+  previous: &(f32, f32),
+  vertex: &(f32, f32),
+  next: &(f32, f32),
+  half_width: f32,
+  join: StrokeJoin,
+) -> f32 {
+  let (Some(a), Some(b)) = (unit(previous, vertex), unit(vertex, next)) else {
+    return half_width;
+  };
+  match mitre_cos(a, b) {
+    cos if join == StrokeJoin::Mitre && cos * MITRE_LIMIT >= 1.0 => half_width / cos,
+    _ => half_width,
+  }
+}
 
-  let len = polygon.len();
-  for i in 1..len + 1 {
-    let p_index = if i < len { i } else { 0 };
-    if target.0 == polygon[p_index].0 && target.1 == polygon[p_index].1 {
-      //Point is on the edge, counts as inside.
-      if border_thickness > 0 {
-        return ContainsResult::Border;
-      }
-      return ContainsResult::Inside;
-    }
-    let seg_point_b = (polygon[p_index].0 as f32, polygon[p_index].1 as f32);
-    // let intersection = path_intersection(&origin, &target, &seg_point_a, &seg_point_b);
-    // println!(
-    //   "{:?}    {:?}  {:?}",
-    //   intersection,
-    //   (origin, target),
-    //   (seg_point_a, seg_point_b)
-    // );
-    if let Some(intersection) = path_intersection(&origin, &target, &seg_point_a, &seg_point_b) {
-      if intersection.0 == seg_point_a.0 && intersection.1 == seg_point_a.1 {
-        //Intersecting on a polygon point, count it so we can remove the duplicate intersections we will find.
-        poly_point_intersections += 1;
-      }
-      intersections += 1;
-      last_intersection_seg_index = i - 1;
-    }
+/** Cosine of the angle between the bisector of a corner from direction `a` to `b` and the normals of its edges, the mitre is 1 / cos border widths long. */
+fn mitre_cos(a: (f32, f32), b: (f32, f32)) -> f32 {
+  let bisector = (a.0 - b.0, a.1 - b.1);
+  let bisector_length = bisector.0.hypot(bisector.1);
+  if bisector_length <= 0.0 {
+    return 1.0;
+  }
+  let along = (bisector.0 * a.0 + bisector.1 * a.1) / bisector_length;
+  (1.0 - along.powi(2)).max(0.0).sqrt()
+}
 
-    seg_point_a = seg_point_b;
+/** Distance from p to the corner at `vertex`, shaped by `join` where p is on the outer side of it. */
+fn corner_distance(
+  previous: &(f32, f32),
+  vertex: &(f32, f32),
+  next: &(f32, f32),
+  p: &(f32, f32),
+  join: StrokeJoin,
+) -> f32 {
+  let d = (p.0 - vertex.0, p.1 - vertex.1);
+  let to_vertex = d.0.hypot(d.1);
+  let incoming = unit(previous, vertex);
+  let outgoing = unit(vertex, next);
+  let (Some(a), Some(b)) = (incoming, outgoing) else {
+    return to_vertex;
+  };
+  let dot = |u: (f32, f32), v: (f32, f32)| u.0 * v.0 + u.1 * v.1;
+  //Past the end of the incoming edge and before the start of the outgoing one.
+  let outer_side = dot(d, a) > 0.0 && dot(d, b) < 0.0;
+  if join == StrokeJoin::Round || !outer_side {
+    return to_vertex;
   }
-  // println!(
-  //   "intersections:{}   {}",
-  //   intersections - poly_point_intersections,
-  //   (intersections - poly_point_intersections) % 2
-  // );
-  if intersections - poly_point_intersections > 0
-    && (intersections - poly_point_intersections) % 2 != 0
-  {
-    //Point is inside poly; check if it is inside border.
-    // println!(
-    //   "dist {:?}  {:?}     {:?}",
-    //   &polygon[last_intersection_seg_index],
-    //   &polygon[(last_intersection_seg_index + 1) % len],
-    //   &p
-    // );
-    if border_thickness > 0 {
-      let distance = distance_to_segment(
-        &polygon[last_intersection_seg_index],
-        &polygon[(last_intersection_seg_index + 1) % len],
-        &target,
-      );
-      if distance < border_thickness as f32 {
-        // println!("distance:{}", distance);
-        ContainsResult::Border
-      } else {
-        ContainsResult::Inside
-      }
-    } else {
-      ContainsResult::Inside
-    }
-  } else {
-    ContainsResult::Outside
+  let mitre = (a.0 * d.1 - a.1 * d.0)
+    .abs()
+    .max((b.0 * d.1 - b.1 * d.0).abs());
+  let bisector = (a.0 - b.0, a.1 - b.1);
+  let bisector_length = bisector.0.hypot(bisector.1);
+  let bisector = (bisector.0 / bisector_length, bisector.1 / bisector_length);
+  let cos = mitre_cos(a, b);
+  if join == StrokeJoin::Mitre && cos * MITRE_LIMIT >= 1.0 {
+    return mitre;
   }
+  if cos <= f32::EPSILON {
+    return to_vertex;
+  }
+  mitre.max(dot(d, bisector) / cos)
+}
+
+/** Direction from a to b with length 1, None if they are the same point. */
+fn unit(a: &(f32, f32), b: &(f32, f32)) -> Option<(f32, f32)> {
+  let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+  let length = dx.hypot(dy);
+  (length > 0.0).then(|| (dx / length, dy / length))
+}
+
+#[derive(PartialEq, Debug)]
+pub(crate) enum ContainsResult {
+  Inside,
+  Outside,
+  Border,
 }
 
-pub fn map_range<T: Copy>(from_range: (T, T), to_range: (T, T), s: T) -> T
+pub fn map_range<T>(from_range: (T, T), to_range: (T, T), s: T) -> T
 where
-  T: Add<T, Output = T> + Sub<T, Output = T> + Mul<T, Output = T> + Div<T, Output = T>,
+  T: Copy + Add<T, Output = T> + Sub<T, Output = T> + Mul<T, Output = T> + Div<T, Output = T>,
 {
   to_range.0 + (s - from_range.0) * (to_range.1 - to_range.0) / (from_range.1 - from_range.0)
 }
 
 #[cfg(test)]
 mod tests {
-  use crate::{debug::print_points, poly_ops::DIAMOND_POLY};
+  use crate::{
+    debug::print_points,
+    raster::{poly_spans, FillRule},
+  };
 
   use super::*;
 
-  static A1: (f32, f32) = (1.0, 1.0);
-  static A2: (f32, f32) = (2.0, 1.0);
-  static B1: (f32, f32) = (1.0, 2.0);
-  static B2: (f32, f32) = (2.0, 2.0);
-  static C1: (f32, f32) = (-1.0, -1.5);
-  static C2: (f32, f32) = (4.0, -1.5);
-  static D1: (f32, f32) = (2.0, -2.0);
-
-  #[test]
-  fn line_intersection_positive_coords() {
-    let result = path_intersection(&A1, &B2, &B1, &A2);
-    assert_eq!(result, Some((1.5, 1.5)));
-  }
-  #[test]
-  fn line_intersection_parallel() {
-    let result = path_intersection(&A1, &A2, &B1, &B2);
-    assert_eq!(result, None);
-  }
-  #[test]
-  fn line_intersection_diverging() {
-    let result = path_intersection(&A1, &C1, &A2, &C2);
-    assert_eq!(result, None);
-  }
-  #[test]
-  fn line_intersection_same_origin() {
-    let result = path_intersection(&A1, &A2, &A1, &B2);
-    assert_eq!(result, Some((1.0, 1.0)));
-  }
-  #[test]
-  fn line_intersection_negative_coords() {
-    let result = path_intersection(&C1, &C2, &D1, &B2);
-    assert_eq!(result, Some((2.0, -1.5)));
-  }
-  #[test]
-  fn line_intersection_on_line() {
-    let result = path_intersection(&A1, &A2, &D1, &B2);
-    assert_eq!(result, Some((2.0, 1.0)));
-  }
-  #[test]
-  fn line_intersection_on_line_2() {
-    let a1 = (0.0, 0.0);
-    let a2 = (2.0, 0.0);
-    let b1 = (-1.0, -1.0);
-    let b2 = (0.0, 0.0);
-    let result = path_intersection(&a1, &a2, &b1, &b2);
-    assert_eq!(result, Some((0.0, 0.0)));
-  }
-
   //
   //
   //Distance to segment tests
@@ -240,39 +261,14 @@ mod tests {
   #[test]
   fn dist_to_segment_diagonal_1() {
     let result = distance_to_segment(&(0.0, 0.0), &(2.0, 2.0), &(0.0, 2.0));
-    assert_eq!(result, 1.4142135);
-  }
-
-  //
-  //
-  //Tests for distance calculations.
-  //
-  #[test]
-  fn distance_u16_0() {
-    let result = distance_u16(0, 0, 0, 0);
-    assert_eq!(result, 0.0);
-  }
-  #[test]
-  fn distance_u16_1() {
-    let result = distance_u16(0, 0, 1, 0);
-    assert_eq!(result, 1.0);
-  }
-  #[test]
-  fn distance_u16_2() {
-    let result = distance_u16(0, 0, 2, 0);
-    assert_eq!(result, 2.0);
-  }
-  #[test]
-  fn distance_u16_diagonal() {
-    let result = distance_u16(0, 0, 1, 1);
-    assert_eq!(result, 1.4142135);
+    assert_eq!(result, std::f32::consts::SQRT_2);
   }
-
   #[rustfmt::skip]
   #[test]
   fn diamond_8by8_contains() {
-    let poly = DIAMOND_POLY.to_vec();
-    print_points(&"diamond", &poly);
+    //Diamond with its tips on the pixels at the edges, pixels are sampled at their centers.
+    let poly: Vec<(f32, f32)> = vec![(4.5, 0.5), (7.5, 4.5), (4.5, 7.5), (0.5, 4.5)];
+    print_points("diamond", &poly);
     let pixels = vec![
       (0, 0), (1, 0), (2, 0), (3, 0), (4, 0), (5, 0), (6, 0), (7, 0),
       (0, 1), (1, 1), (2, 1), (3, 1), (4, 1), (5, 1), (6, 1), (7, 1),
@@ -283,10 +279,13 @@ mod tests {
       (0, 6), (1, 6), (2, 6), (3, 6), (4, 6), (5, 6), (6, 6), (7, 6),
       (0, 7), (1, 7), (2, 7), (3, 7), (4, 7), (5, 7), (6, 7), (7, 7),
     ];
-    let result: Vec<ContainsResult> = pixels.iter().map(|p| {
-      let c = poly_contains(&poly, p, 0);
-      // println!("{},{}      c:{:?}", p.0, p.1, c);
-      c
+    let spans = poly_spans(&[&poly], 8, 8, FillRule::EvenOdd);
+    let result: Vec<ContainsResult> = pixels.iter().map(|p: &(u16, u16)| {
+      if spans.iter().any(|s| s.y == p.1 && (s.x_start..=s.x_end).contains(&p.0)) {
+        ContainsResult::Inside
+      } else {
+        ContainsResult::Outside
+      }
     }).collect();
     assert_eq!(result, vec![
       ContainsResult::Outside, ContainsResult::Outside, ContainsResult::Outside, ContainsResult::Outside, ContainsResult::Inside,  ContainsResult::Outside, ContainsResult::Outside, ContainsResult::Outside,
@@ -299,90 +298,130 @@ mod tests {
       ContainsResult::Outside, ContainsResult::Outside, ContainsResult::Outside, ContainsResult::Outside, ContainsResult::Inside,  ContainsResult::Outside, ContainsResult::Outside, ContainsResult::Outside,
     ]);
   }
-  #[rustfmt::skip]
+
+  //
+  //
+  //Tests for distance calculations.
+  //
   #[test]
-  fn is_0_0_in_2by2() {
-    let polygon = vec![
-      (0.0, 0.0),
-      (2.0, 0.0),
-      (2.0, 2.0),
-      (0.0, 2.0)
-    ];
-    let result = poly_contains(&polygon, &(0, 0), 0);
-    assert_eq!(result, ContainsResult::Inside);
+  fn distance_u16_0() {
+    let result = distance_u16(0, 0, 0, 0);
+    assert_eq!(result, 0.0);
   }
-  #[rustfmt::skip]
   #[test]
-  fn is_0_0_outside_2by2_diamond() {
-    let polygon = vec![
-      (2.0, 1.0),
-      (1.0, 2.0),
-      (0.0, 1.0),
-      (1.0, 0.0)
-    ];
-    let result = poly_contains(&polygon, &(0, 0), 0);
-    assert_eq!(result, ContainsResult::Outside);
+  fn distance_u16_1() {
+    let result = distance_u16(0, 0, 1, 0);
+    assert_eq!(result, 1.0);
+  }
+  #[test]
+  fn distance_u16_2() {
+    let result = distance_u16(0, 0, 2, 0);
+    assert_eq!(result, 2.0);
+  }
+  #[test]
+  fn distance_u16_diagonal() {
+    let result = distance_u16(0, 0, 1, 1);
+    assert_eq!(result, std::f32::consts::SQRT_2);
   }
 
+  //
+  //
+  //Distance to outline tests
+  //
   #[rustfmt::skip]
+  static SQUARE: [(f32, f32); 4] = [
+    (0.0, 0.0),
+    (4.0, 0.0),
+    (4.0, 4.0),
+    (0.0, 4.0),
+  ];
   #[test]
-  fn is_0_0_border_in_2by2() {
-    let polygon = vec![
-      (0.0, 0.0),
-      (2.0, 0.0),
-      (2.0, 2.0),
-      (0.0, 2.0)
-    ];
-    let result = poly_contains(&polygon, &(0, 0), 1);
-    assert_eq!(result, ContainsResult::Border);
+  fn dist_to_polyline_caps() {
+    let line = [(0.0, 0.0), (4.0, 0.0)];
+    let distance =
+      |p: (f32, f32), cap: StrokeCap| distance_to_polyline(&line, &p, 1.0, StrokeJoin::Mitre, cap);
+    assert_eq!(distance((2.0, 0.5), StrokeCap::Butt), 0.5);
+    //Past the end by 0.5 and 0.5 to the side.
+    assert_eq!(distance((4.5, 0.5), StrokeCap::Butt), 1.5);
+    assert_eq!(distance((4.5, 0.5), StrokeCap::Square), 0.5);
+    assert!((distance((4.5, 0.5), StrokeCap::Round) - 0.5_f32.hypot(0.5)).abs() < 1e-6);
+    assert_eq!(distance((-0.5, 0.0), StrokeCap::Square), 0.5);
+    let dot = distance_to_polyline(
+      &[(1.0, 1.0), (1.0, 1.0)],
+      &(2.0, 2.0),
+      1.0,
+      StrokeJoin::Mitre,
+      StrokeCap::Square,
+    );
+    assert_eq!(dot, 1.0);
   }
-  #[rustfmt::skip]
   #[test]
-  fn is_1_1_not_border_in_64by64() {
-    let polygon = vec![
-      (0.0, 0.0),
-      (64.0, 0.0),
-      (64.0, 64.0),
-      (0.0, 64.0)
-    ];
-    let result = poly_contains(&polygon, &(1, 1), 1);
-    assert_ne!(result, ContainsResult::Border);
+  fn dist_to_polyline_joins() {
+    let corner = [(0.0, 4.0), (0.0, 0.0), (4.0, 0.0)];
+    let distance =
+      |join: StrokeJoin| distance_to_polyline(&corner, &(-1.0, -1.0), 1.0, join, StrokeCap::Butt);
+    assert_eq!(distance(StrokeJoin::Mitre), 1.0);
+    assert!((distance(StrokeJoin::Round) - std::f32::consts::SQRT_2).abs() < 1e-6);
+    assert!(distance(StrokeJoin::Bevel) > 1.0);
+    //The open ends are not joined to each other.
+    let open = distance_to_polyline(
+      &corner,
+      &(2.0, 2.0),
+      1.0,
+      StrokeJoin::Mitre,
+      StrokeCap::Butt,
+    );
+    assert_eq!(open, 2.0);
   }
-  #[rustfmt::skip]
   #[test]
-  fn is_1_1_border_in_64by64() {
-    let polygon = vec![
-      (0.0, 0.0),
-      (64.0, 0.0),
-      (64.0, 64.0),
-      (0.0, 64.0)
-    ];
-    let result = poly_contains(&polygon, &(1, 1), 2);
-    assert_eq!(result, ContainsResult::Border);
+  fn dist_to_outline_uses_closing_edge() {
+    let triangle = [(0.0, 0.0), (4.0, 0.0), (0.0, 4.0)];
+    assert_eq!(
+      distance_to_outline(&triangle, &(-1.0, 2.0), StrokeJoin::Round),
+      1.0
+    );
+    assert_eq!(
+      distance_to_outline(&triangle, &(2.0, 2.0), StrokeJoin::Round),
+      0.0
+    );
   }
-
-  #[rustfmt::skip]
   #[test]
-  fn is_1_1_in_2by2() {
-    let polygon = vec![
-      (0.0, 0.0),
-      (2.0, 0.0),
-      (2.0, 2.0),
-      (0.0, 2.0)
-    ];
-    let result = poly_contains(&polygon, &(1, 1), 0);
-    assert_eq!(result, ContainsResult::Inside);
+  fn dist_to_outline_is_nearest_edge() {
+    //The last edge a ray from the top left crosses is not the closest one.
+    let poly = [(0.0, 0.0), (64.0, 0.0), (64.0, 64.0), (0.0, 64.0)];
+    assert_eq!(
+      distance_to_outline(&poly, &(1.5, 60.5), StrokeJoin::Mitre),
+      1.5
+    );
+    assert_eq!(
+      distance_to_outline(&poly, &(62.5, 3.5), StrokeJoin::Mitre),
+      1.5
+    );
   }
-  #[rustfmt::skip]
   #[test]
-  fn is_1_3_not_in_2by2() {
-    let polygon = vec![
-      (0.0, 0.0),
-      (2.0, 0.0),
-      (2.0, 2.0),
-      (0.0, 2.0)
-    ];
-    let result = poly_contains(&polygon, &(1, 3), 0);
-    assert_eq!(result, ContainsResult::Outside);
+  fn dist_to_outline_joins() {
+    let outside = (-1.0, -1.0);
+    assert_eq!(
+      distance_to_outline(&SQUARE, &outside, StrokeJoin::Round),
+      std::f32::consts::SQRT_2
+    );
+    assert_eq!(
+      distance_to_outline(&SQUARE, &outside, StrokeJoin::Mitre),
+      1.0
+    );
+    assert!((distance_to_outline(&SQUARE, &outside, StrokeJoin::Bevel) - 2.0).abs() < 0.0001);
+    //Inside of the corner every join measures to the nearest edge.
+    for join in [StrokeJoin::Round, StrokeJoin::Mitre, StrokeJoin::Bevel] {
+      assert_eq!(distance_to_outline(&SQUARE, &(1.0, 0.5), join), 0.5);
+    }
+  }
+  #[test]
+  fn dist_to_outline_sharp_mitre_is_beveled() {
+    let spike = [(0.0, 0.0), (10.0, 1.0), (0.0, 2.0)];
+    let tip = (11.0, 1.0);
+    let mitre = distance_to_outline(&spike, &tip, StrokeJoin::Mitre);
+    assert_eq!(mitre, distance_to_outline(&spike, &tip, StrokeJoin::Bevel));
+    assert!(mitre > 10.0);
+    assert_eq!(distance_to_outline(&spike, &tip, StrokeJoin::Round), 1.0);
   }
 }
@@ -0,0 +1,209 @@
+use crate::colors::mix_color;
+
+/**
+What a fill or border is painted with.
+
+Gradients are evaluated in the coordinate space of the box they are painted in, with (0, 0) at the top left corner and (1, 1) at the bottom right corner. Stops are `(offset, color)` pairs with offsets in range [0, 1], in increasing order. A stop with a smaller offset than the one before it is moved up to that offset, which gives a hard edge between the two colors.
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub enum Paint {
+  Solid([u8; 4]),
+  /**
+  Colors change along a line through the center of the box.
+
+  `angle` is in degrees, 0 goes from bottom to top and 90 from left to right. The line is long enough that offset 0 and 1 are exactly at the corners of the box.
+  */
+  LinearGradient {
+    angle: f32,
+    stops: Vec<(f32, [u8; 4])>,
+  },
+  /**
+  Colors change with the distance from `center`.
+
+  `radius` is relative to the box, so a radius of 0.5 at center (0.5, 0.5) reaches the middle of each edge. Boxes that are not square get an elliptical gradient.
+  */
+  RadialGradient {
+    center: (f32, f32),
+    radius: f32,
+    stops: Vec<(f32, [u8; 4])>,
+  },
+  /** Colors change clockwise around `center`, starting at `angle` degrees where 0 is straight up. */
+  ConicGradient {
+    center: (f32, f32),
+    angle: f32,
+    stops: Vec<(f32, [u8; 4])>,
+  },
+}
+impl From<[u8; 4]> for Paint {
+  fn from(color: [u8; 4]) -> Self {
+    Paint::Solid(color)
+  }
+}
+
+impl Paint {
+  /** Color of pixel (x, y) in the box from (left, top) to (right, bottom), edges included. */
+  pub(crate) fn color_at(
+    &self,
+    x: &u16,
+    y: &u16,
+    left: &u16,
+    top: &u16,
+    right: &u16,
+    bottom: &u16,
+  ) -> [u8; 4] {
+    let (stops, t) = match self {
+      Paint::Solid(color) => return *color,
+      _ if right < left || bottom < top => return [0, 0, 0, 0],
+      Paint::LinearGradient { angle, stops } => {
+        let (w, h, px, py) = box_position(x, y, left, top, right, bottom);
+        let (sin, cos) = angle.to_radians().sin_cos();
+        let length = (w * sin).abs() + (h * cos).abs();
+        let along = (px - w / 2.0) * sin - (py - h / 2.0) * cos;
+        (stops, along / length + 0.5)
+      }
+      Paint::RadialGradient {
+        center,
+        radius,
+        stops,
+      } => {
+        if *radius <= 0.0 {
+          (stops, 1.0)
+        } else {
+          let (w, h, px, py) = box_position(x, y, left, top, right, bottom);
+          let dx = (px - center.0 * w) / (radius * w);
+          let dy = (py - center.1 * h) / (radius * h);
+          (stops, (dx * dx + dy * dy).sqrt())
+        }
+      }
+      Paint::ConicGradient {
+        center,
+        angle,
+        stops,
+      } => {
+        let (w, h, px, py) = box_position(x, y, left, top, right, bottom);
+        let degrees = (px - center.0 * w).atan2(center.1 * h - py).to_degrees();
+        (stops, (degrees - angle).rem_euclid(360.0) / 360.0)
+      }
+    };
+    sample_stops(stops, t)
+  }
+}
+
+/** Size of the box and position of the pixel center inside of it, in pixels. */
+fn box_position(
+  x: &u16,
+  y: &u16,
+  left: &u16,
+  top: &u16,
+  right: &u16,
+  bottom: &u16,
+) -> (f32, f32, f32, f32) {
+  (
+    (right - left + 1) as f32,
+    (bottom - top + 1) as f32,
+    *x as f32 - *left as f32 + 0.5,
+    *y as f32 - *top as f32 + 0.5,
+  )
+}
+
+fn sample_stops(stops: &[(f32, [u8; 4])], t: f32) -> [u8; 4] {
+  let Some(first) = stops.first() else {
+    return [0, 0, 0, 0];
+  };
+  if t <= first.0 {
+    return first.1;
+  }
+  let mut previous = *first;
+  for stop in &stops[1..] {
+    let offset = stop.0.max(previous.0);
+    if t < offset {
+      return mix_color(
+        &previous.1,
+        &stop.1,
+        (t - previous.0) / (offset - previous.0),
+      );
+    }
+    previous = (offset, stop.1);
+  }
+  previous.1
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const BLACK: [u8; 4] = [0, 0, 0, 255];
+  const WHITE: [u8; 4] = [255, 255, 255, 255];
+
+  fn at(paint: &Paint, x: u16, y: u16) -> [u8; 4] {
+    paint.color_at(&x, &y, &0, &0, &9, &9)
+  }
+
+  #[test]
+  fn solid_ignores_position() {
+    let paint = Paint::from([1, 2, 3, 4]);
+    assert_eq!(at(&paint, 0, 0), [1, 2, 3, 4]);
+    assert_eq!(at(&paint, 9, 9), [1, 2, 3, 4]);
+  }
+  #[test]
+  fn linear_gradient_follows_angle() {
+    let paint = Paint::LinearGradient {
+      angle: 90.0,
+      stops: vec![(0.0, BLACK), (1.0, WHITE)],
+    };
+    assert_eq!(at(&paint, 0, 0), at(&paint, 0, 9));
+    assert!(at(&paint, 0, 5)[0] < 20);
+    assert!(at(&paint, 9, 5)[0] > 235);
+    assert!(at(&paint, 4, 0)[0] < 128 && at(&paint, 5, 0)[0] > 128);
+
+    let up = Paint::LinearGradient {
+      angle: 0.0,
+      stops: vec![(0.0, BLACK), (1.0, WHITE)],
+    };
+    assert!(at(&up, 5, 0)[0] > 235);
+    assert!(at(&up, 5, 9)[0] < 20);
+  }
+  #[test]
+  fn diagonal_gradient_reaches_corners() {
+    let paint = Paint::LinearGradient {
+      angle: 135.0,
+      stops: vec![(0.0, BLACK), (1.0, WHITE)],
+    };
+    //Centers of the corner pixels are half a pixel inside of the corners.
+    assert_eq!(at(&paint, 0, 0), [13, 13, 13, 255]);
+    assert_eq!(at(&paint, 9, 9), [242, 242, 242, 255]);
+    assert_eq!(at(&paint, 9, 0), at(&paint, 0, 9));
+  }
+  #[test]
+  fn radial_gradient_is_symmetric() {
+    let paint = Paint::RadialGradient {
+      center: (0.5, 0.5),
+      radius: 0.5,
+      stops: vec![(0.0, WHITE), (1.0, BLACK)],
+    };
+    assert_eq!(at(&paint, 0, 0), BLACK);
+    assert_eq!(at(&paint, 4, 4), at(&paint, 5, 5));
+    assert_eq!(at(&paint, 2, 4), at(&paint, 4, 2));
+    assert!(at(&paint, 4, 4)[0] > 200);
+  }
+  #[test]
+  fn conic_gradient_goes_clockwise() {
+    let paint = Paint::ConicGradient {
+      center: (0.5, 0.5),
+      angle: 0.0,
+      stops: vec![(0.0, BLACK), (1.0, WHITE)],
+    };
+    let right = at(&paint, 9, 5)[0];
+    let bottom = at(&paint, 5, 9)[0];
+    let left = at(&paint, 0, 4)[0];
+    assert!(right < bottom && bottom < left);
+  }
+  #[test]
+  fn stops_clamp_and_hard_edges() {
+    let stops = vec![(0.25, BLACK), (0.5, WHITE), (0.25, [255, 0, 0, 255])];
+    assert_eq!(sample_stops(&stops, 0.0), BLACK);
+    assert_eq!(sample_stops(&stops, 0.375), [128, 128, 128, 255]);
+    assert_eq!(sample_stops(&stops, 0.6), [255, 0, 0, 255]);
+    assert_eq!(sample_stops(&[], 0.5), [0, 0, 0, 0]);
+  }
+}
@@ -0,0 +1,633 @@
+use std::f32::consts::{PI, SQRT_2};
+
+use crate::{
+  border_style::{StrokeCap, StrokeJoin},
+  colors::{add_color_set_pixel, scale_alpha},
+  error::{check_coordinate, check_range, SpriteorError, LENGTH_RANGE},
+  maths::{
+    arc_step, corner_reach, dot_distance, outline_piece_distance, polyline_piece_distance, xy_to_i,
+    MITRE_LIMIT,
+  },
+  paint::Paint,
+  poly_ops::{edge_coverage, outline_pixels, PolyFit},
+  raster::{CellGrid, FillRule},
+  rect_ops::{resolve_area, RectOpUnw},
+  svg_path::parse_svg_path,
+};
+
+/** Most straight segments a single curve is split into, which is plenty for any curve that fits on a sprite. */
+const MAX_CURVE_STEPS: f32 = 1024.0;
+
+/** Drawing command of a [`SpriteorPathOp`], like the commands of SVG paths with absolute coordinates. */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PathCommand {
+  /** Starts a new subpath at (x, y). */
+  MoveTo(f32, f32),
+  /** Straight line to (x, y). */
+  LineTo(f32, f32),
+  /** Quadratic Bézier curve to `to`, pulled towards `control`. */
+  QuadTo { control: (f32, f32), to: (f32, f32) },
+  /** Cubic Bézier curve to `to`, leaving towards `control_a` and arriving from `control_b`. */
+  CubicTo {
+    control_a: (f32, f32),
+    control_b: (f32, f32),
+    to: (f32, f32),
+  },
+  /**
+  Elliptical arc to `to` like the SVG `A` command.
+
+  `radii` (x, y) are grown if they are too small to reach `to`, `rotation` turns the x axis of the ellipse clockwise in degrees. Of the four arcs that fit, `large_arc` picks one of the two that cover more than 180 degrees and `sweep` one that goes clockwise.
+  */
+  ArcTo {
+    radii: (f32, f32),
+    rotation: f32,
+    large_arc: bool,
+    sweep: bool,
+    to: (f32, f32),
+  },
+  /** Closes the subpath with a straight line back to where it started. */
+  Close,
+}
+
+/** Path of straight lines and curves, filled and stroked like [`SpriteorPolyOp`](crate::SpriteorPolyOp) polygons. */
+#[derive(Debug, Clone)]
+pub struct SpriteorPathOp {
+  /**
+  Coordinates (x, y) of the area the path is drawn in. Must be in range [-4096, 4096]. Negative values are counted from right-edge for x and bottom for y.
+
+  Works like `point_a` of [`SpriteorRectOp`](crate::SpriteorRectOp), defaults to the whole container.
+  */
+  pub point_a: Option<(i16, i16)>,
+  /** Opposite corner of the area from `point_a`, works like `point_b` of [`SpriteorRectOp`](crate::SpriteorRectOp). */
+  pub point_b: Option<(i16, i16)>,
+  /** Commands with points in range [0, 1] that are scaled to the area. Paths start at (0, 0) until the first `MoveTo`. */
+  pub commands: Vec<PathCommand>,
+  /** How the path is scaled to the area. Defaults to stretch. */
+  pub fit: PolyFit,
+  /** Position (x, y) of the path in range [0, 1] when `fit` leaves space around it or cuts it off. Defaults to centered. */
+  pub align: (f32, f32),
+  /** Decides what is filled where subpaths overlap. Defaults to even-odd. */
+  pub fill_rule: FillRule,
+  /** Paint inside of the path, open subpaths are filled as if they were closed. None leaves the path unfilled, which is the default. */
+  pub fill_color: Option<Paint>,
+  /** Width of the line along the path in pixels, centered on it, must be in range [0, 4096]. 0 draws no line. Defaults to 1. */
+  pub stroke_width: u16,
+  /** Paint of the line along the path. Defaults to white. */
+  pub stroke_color: Option<Paint>,
+  /** Shape of the ends of open subpaths. Defaults to butt. */
+  pub stroke_cap: StrokeCap,
+  /** Shape of the line where segments meet. Defaults to mitre. */
+  pub stroke_join: StrokeJoin,
+  /** Max distance in pixels between the curves and the straight segments they are drawn with. Defaults to 0.1. */
+  pub tolerance: f32,
+  /** Smooths the edges of the fill and line with partially transparent pixels. Disable for pixel art styles. Defaults to true. */
+  pub anti_alias: bool,
+}
+impl SpriteorPathOp {
+  /**
+  Path op from the data of an SVG path `d` attribute, drawn like SVG by default: filled with the nonzero rule and without a line.
+
+  `view_box` (x, y, width, height) is the `viewBox` of the SVG. It is scaled to the unit square keeping its aspect ratio and centered in the smaller direction, so `fit` places the icon in the area like any other path. Its width and height must be positive.
+
+  ```
+  # use ui_spriteor::prelude::*;
+  let check = SpriteorPathOp::from_svg("M9 16.2 4.8 12l-1.4 1.4L9 19 21 7l-1.4-1.4z", (0.0, 0.0, 24.0, 24.0))?;
+  let op = SpriteorPathOp {
+    fit: PolyFit::Contain,
+    fill_color: Some(Paint::Solid([40, 200, 80, 255])),
+    ..check
+  };
+  # Ok::<(), SpriteorError>(())
+  ```
+  */
+  pub fn from_svg(
+    data: &str,
+    view_box: (f32, f32, f32, f32),
+  ) -> Result<SpriteorPathOp, SpriteorError> {
+    let (x, y, width, height) = view_box;
+    let is_finite = [x, y, width, height].iter().all(|value| value.is_finite());
+    if !is_finite || width <= 0.0 || height <= 0.0 {
+      return Err(SpriteorError::InvalidViewBox);
+    }
+    let scale = 1.0 / width.max(height);
+    let offset = ((1.0 - width * scale) / 2.0, (1.0 - height * scale) / 2.0);
+    let map = |p: (f32, f32)| ((p.0 - x) * scale + offset.0, (p.1 - y) * scale + offset.1);
+    let commands = parse_svg_path(data)?
+      .into_iter()
+      .map(|command| match command {
+        PathCommand::MoveTo(x, y) => {
+          let (x, y) = map((x, y));
+          PathCommand::MoveTo(x, y)
+        }
+        PathCommand::LineTo(x, y) => {
+          let (x, y) = map((x, y));
+          PathCommand::LineTo(x, y)
+        }
+        PathCommand::QuadTo { control, to } => PathCommand::QuadTo {
+          control: map(control),
+          to: map(to),
+        },
+        PathCommand::CubicTo {
+          control_a,
+          control_b,
+          to,
+        } => PathCommand::CubicTo {
+          control_a: map(control_a),
+          control_b: map(control_b),
+          to: map(to),
+        },
+        PathCommand::ArcTo {
+          radii,
+          rotation,
+          large_arc,
+          sweep,
+          to,
+        } => PathCommand::ArcTo {
+          radii: (radii.0 * scale, radii.1 * scale),
+          rotation,
+          large_arc,
+          sweep,
+          to: map(to),
+        },
+        PathCommand::Close => PathCommand::Close,
+      })
+      .collect();
+    Ok(SpriteorPathOp {
+      commands,
+      fill_rule: FillRule::NonZero,
+      fill_color: Some(Paint::Solid([255, 255, 255, 255])),
+      stroke_width: 0,
+      ..Default::default()
+    })
+  }
+  pub(crate) fn validate(&self) -> Result<(), SpriteorError> {
+    for point in [self.point_a, self.point_b].iter().flatten() {
+      check_coordinate(point.0 as i32, point.1 as i32)?;
+    }
+    check_range("stroke_width", self.stroke_width as i32, LENGTH_RANGE)
+  }
+  pub(crate) fn add_to(&self, values: &mut [u8], container: &RectOpUnw, sprite_width: &u16) {
+    let (top, right, bottom, left) = resolve_area(
+      self.point_a,
+      self.point_b,
+      (
+        container.border_box_top,
+        container.border_box_right,
+        container.border_box_bottom,
+        container.border_box_left,
+      ),
+    );
+    let (scale, offset) = self.fit.place(
+      self.align,
+      (right - left + 1) as f32,
+      (bottom - top + 1) as f32,
+    );
+    let origin = (left as f32 + offset.0, top as f32 + offset.1);
+    let subpaths = flatten(&self.commands, origin, scale, self.tolerance);
+    if subpaths.is_empty() {
+      return;
+    }
+
+    //Lines can reach past the area by their mitres, covering paths are cut off at it.
+    let half_width = self.stroke_width as f32 / 2.0;
+    let reach = if self.fit == PolyFit::Cover {
+      0
+    } else {
+      (half_width * MITRE_LIMIT).ceil() as i32 + 1
+    };
+    let sprite_height = (values.len() / 4 / *sprite_width as usize) as i32;
+    let box_left = (left as i32 - reach).max(0);
+    let box_top = (top as i32 - reach).max(0);
+    let box_width = ((right as i32 + reach).min(*sprite_width as i32 - 1) - box_left + 1).max(0);
+    let box_height = ((bottom as i32 + reach).min(sprite_height - 1) - box_top + 1).max(0);
+    let center = |x: i32, y: i32| (x as f32 + 0.5, y as f32 + 0.5);
+    let paint_at =
+      |paint: &Paint, x: u16, y: u16| paint.color_at(&x, &y, &left, &top, &right, &bottom);
+
+    if let Some(fill_color) = &self.fill_color {
+      let contours: Vec<Vec<(f32, f32)>> = subpaths
+        .iter()
+        .map(|(points, _)| {
+          points
+            .iter()
+            .map(|p| (p.0 - box_left as f32, p.1 - box_top as f32))
+            .collect()
+        })
+        .collect();
+      //Pixels further than half a pixel from the outline are fully inside or outside.
+      let pixels = outline_pixels(
+        &contours,
+        (box_width as u16, box_height as u16),
+        self.fill_rule,
+        0,
+        self.anti_alias.then_some(1.0),
+        |inside, distance| {
+          edge_coverage(
+            inside,
+            distance,
+            (0.0, 0.0),
+            StrokeJoin::Round,
+            self.anti_alias,
+          )
+        },
+      );
+      let runs = pixels
+        .runs
+        .iter()
+        .flat_map(|(y, x_start, x_end)| (*x_start..=*x_end).map(move |x| (x, *y, 1.0, 1.0)));
+      for (x, y, coverage, _) in runs.chain(pixels.edge) {
+        let (x, y) = ((x + box_left) as u16, (y + box_top) as u16);
+        add_color_set_pixel(
+          values,
+          &xy_to_i(sprite_width, &x, &y),
+          &scale_alpha(&paint_at(fill_color, x, y), coverage),
+        );
+      }
+    }
+
+    if self.stroke_width == 0 {
+      return;
+    }
+    let stroke_color = self
+      .stroke_color
+      .clone()
+      .unwrap_or(Paint::Solid([255, 255, 255, 255]));
+    //Every segment is measured together with the corner at its start, pixels only measure the ones that can reach them.
+    let subpaths: Vec<(Vec<(f32, f32)>, bool)> = subpaths
+      .into_iter()
+      .map(|(mut points, closed)| {
+        if !closed {
+          points.dedup();
+        }
+        (points, closed)
+      })
+      .collect();
+    let mut pieces = Vec::new();
+    let mut grid = CellGrid::new(box_width as u16, box_height as u16, 16);
+    for (subpath, (points, closed)) in subpaths.iter().enumerate() {
+      let len = points.len();
+      let count = if *closed || len == 1 { len } else { len - 1 };
+      for i in 0..count {
+        let (a, b) = (points[i], points[(i + 1) % len]);
+        let has_corner = *closed || (i > 0 && i + 1 < len);
+        let corner = if has_corner {
+          corner_reach(
+            &points[(i + len - 1) % len],
+            &a,
+            &b,
+            half_width,
+            self.stroke_join,
+          )
+        } else {
+          0.0
+        };
+        //Square caps reach furthest at their corners.
+        let piece_reach = (half_width * SQRT_2).max(corner) + 1.0;
+        let (min, max) = ((a.0.min(b.0), a.1.min(b.1)), (a.0.max(b.0), a.1.max(b.1)));
+        grid.insert(
+          pieces.len(),
+          (
+            min.0 - piece_reach - box_left as f32,
+            min.1 - piece_reach - box_top as f32,
+          ),
+          (
+            max.0 + piece_reach - box_left as f32,
+            max.1 + piece_reach - box_top as f32,
+          ),
+        );
+        pieces.push((subpath, i));
+      }
+    }
+    let cell = grid.cell_size();
+    for (cell_left, cell_top, items) in grid.occupied() {
+      for y in box_top + cell_top..box_top + (cell_top + cell).min(box_height) {
+        for x in box_left + cell_left..box_left + (cell_left + cell).min(box_width) {
+          let p = center(x, y);
+          let distance = items
+            .iter()
+            .map(|&item| {
+              let (subpath, i) = pieces[item];
+              let (points, closed) = &subpaths[subpath];
+              if *closed {
+                outline_piece_distance(points, i, &p, self.stroke_join)
+              } else if let [point] = points[..] {
+                dot_distance(&point, &p, self.stroke_cap)
+              } else {
+                polyline_piece_distance(
+                  points,
+                  i,
+                  &p,
+                  half_width,
+                  self.stroke_join,
+                  self.stroke_cap,
+                )
+              }
+            })
+            .fold(f32::MAX, f32::min);
+          let coverage = if self.anti_alias {
+            (half_width + 0.5 - distance).clamp(0.0, 1.0)
+          } else if distance < half_width {
+            1.0
+          } else {
+            0.0
+          };
+          if coverage > 0.0 {
+            let (x, y) = (x as u16, y as u16);
+            add_color_set_pixel(
+              values,
+              &xy_to_i(sprite_width, &x, &y),
+              &scale_alpha(&paint_at(&stroke_color, x, y), coverage),
+            );
+          }
+        }
+      }
+    }
+  }
+}
+impl Default for SpriteorPathOp {
+  fn default() -> Self {
+    SpriteorPathOp {
+      point_a: None,
+      point_b: None,
+      commands: Vec::new(),
+      fit: PolyFit::Stretch,
+      align: (0.5, 0.5),
+      fill_rule: FillRule::EvenOdd,
+      fill_color: None,
+      stroke_width: 1,
+      stroke_color: None,
+      stroke_cap: StrokeCap::Butt,
+      stroke_join: StrokeJoin::Mitre,
+      tolerance: 0.1,
+      anti_alias: true,
+    }
+  }
+}
+
+/**
+Turns the commands into subpaths of straight segments in pixels, as (points, closed).
+
+Points are placed at `origin + point * scale`. Curves are split into as many segments as needed to stay within `tolerance` pixels, using the bound of Wang's formula for Béziers and the chord height for arcs.
+*/
+pub(crate) fn flatten(
+  commands: &[PathCommand],
+  origin: (f32, f32),
+  scale: (f32, f32),
+  tolerance: f32,
+) -> Vec<(Vec<(f32, f32)>, bool)> {
+  let to_pixels = |p: (f32, f32)| (origin.0 + p.0 * scale.0, origin.1 + p.1 * scale.1);
+  let tolerance = tolerance.max(0.001);
+  let mut subpaths = Vec::new();
+  let mut points: Vec<(f32, f32)> = Vec::new();
+  //Current point and start of the subpath in path coordinates.
+  let (mut current, mut start) = ((0.0, 0.0), (0.0, 0.0));
+  let mut finish = |points: &mut Vec<(f32, f32)>, closed: bool| {
+    if !points.is_empty() {
+      subpaths.push((std::mem::take(points), closed));
+    }
+  };
+  for command in commands {
+    if points.is_empty() && !matches!(command, PathCommand::MoveTo(..)) {
+      points.push(to_pixels(current));
+    }
+    match *command {
+      PathCommand::MoveTo(x, y) => {
+        finish(&mut points, false);
+        current = (x, y);
+        start = current;
+        points.push(to_pixels(current));
+      }
+      PathCommand::LineTo(x, y) => {
+        current = (x, y);
+        points.push(to_pixels(current));
+      }
+      PathCommand::QuadTo { control, to } => {
+        let (a, b, c) = (to_pixels(current), to_pixels(control), to_pixels(to));
+        let bend = (a.0 - 2.0 * b.0 + c.0).hypot(a.1 - 2.0 * b.1 + c.1);
+        let steps = ((bend / (4.0 * tolerance))
+          .sqrt()
+          .ceil()
+          .clamp(1.0, MAX_CURVE_STEPS) as usize)
+          .max(1);
+        for step in 1..=steps {
+          let t = step as f32 / steps as f32;
+          let u = 1.0 - t;
+          points.push((
+            u * u * a.0 + 2.0 * u * t * b.0 + t * t * c.0,
+            u * u * a.1 + 2.0 * u * t * b.1 + t * t * c.1,
+          ));
+        }
+        current = to;
+      }
+      PathCommand::CubicTo {
+        control_a,
+        control_b,
+        to,
+      } => {
+        let (a, b, c, d) = (
+          to_pixels(current),
+          to_pixels(control_a),
+          to_pixels(control_b),
+          to_pixels(to),
+        );
+        let bend = (a.0 - 2.0 * b.0 + c.0)
+          .hypot(a.1 - 2.0 * b.1 + c.1)
+          .max((b.0 - 2.0 * c.0 + d.0).hypot(b.1 - 2.0 * c.1 + d.1));
+        let steps = ((3.0 * bend / (4.0 * tolerance))
+          .sqrt()
+          .ceil()
+          .clamp(1.0, MAX_CURVE_STEPS) as usize)
+          .max(1);
+        for step in 1..=steps {
+          let t = step as f32 / steps as f32;
+          let u = 1.0 - t;
+          let (w_a, w_b, w_c, w_d) = (u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t);
+          points.push((
+            w_a * a.0 + w_b * b.0 + w_c * c.0 + w_d * d.0,
+            w_a * a.1 + w_b * b.1 + w_c * c.1 + w_d * d.1,
+          ));
+        }
+        current = to;
+      }
+      PathCommand::ArcTo {
+        radii,
+        rotation,
+        large_arc,
+        sweep,
+        to,
+      } => {
+        let arc = arc_points(current, to, radii, rotation, large_arc, sweep, |radius| {
+          arc_step(radius * scale.0.abs().max(scale.1.abs()), tolerance)
+        });
+        points.extend(arc.into_iter().map(to_pixels));
+        current = to;
+      }
+      PathCommand::Close => {
+        finish(&mut points, true);
+        current = start;
+      }
+    }
+  }
+  finish(&mut points, false);
+  subpaths
+}
+
+/**
+Points after `from` along an SVG arc up to and including `to`, in path coordinates.
+
+Follows the endpoint to center conversion of the SVG specification. `max_step` gives the largest angle in radians between points for a radius.
+*/
+fn arc_points(
+  from: (f32, f32),
+  to: (f32, f32),
+  radii: (f32, f32),
+  rotation: f32,
+  large_arc: bool,
+  sweep: bool,
+  max_step: impl Fn(f32) -> f32,
+) -> Vec<(f32, f32)> {
+  let (mut rx, mut ry) = (radii.0.abs(), radii.1.abs());
+  if from == to {
+    return Vec::new();
+  }
+  if rx == 0.0 || ry == 0.0 {
+    return vec![to];
+  }
+  let (sin, cos) = rotation.to_radians().sin_cos();
+  let half = ((from.0 - to.0) / 2.0, (from.1 - to.1) / 2.0);
+  let x1 = cos * half.0 + sin * half.1;
+  let y1 = -sin * half.0 + cos * half.1;
+  //Radii too small to reach `to` are scaled up until the arc is exactly half an ellipse.
+  let lambda = (x1 / rx).powi(2) + (y1 / ry).powi(2);
+  if lambda > 1.0 {
+    rx *= lambda.sqrt();
+    ry *= lambda.sqrt();
+  }
+  let numerator = (rx * ry).powi(2) - (rx * y1).powi(2) - (ry * x1).powi(2);
+  let denominator = (rx * y1).powi(2) + (ry * x1).powi(2);
+  let sign = if large_arc == sweep { -1.0 } else { 1.0 };
+  let coefficient = sign * (numerator / denominator).max(0.0).sqrt();
+  let (cx1, cy1) = (coefficient * rx * y1 / ry, -coefficient * ry * x1 / rx);
+  let center = (
+    cos * cx1 - sin * cy1 + (from.0 + to.0) / 2.0,
+    sin * cx1 + cos * cy1 + (from.1 + to.1) / 2.0,
+  );
+  let start_angle = ((y1 - cy1) / ry).atan2((x1 - cx1) / rx);
+  let end_angle = ((-y1 - cy1) / ry).atan2((-x1 - cx1) / rx);
+  let mut delta = (end_angle - start_angle).rem_euclid(2.0 * PI);
+  if !sweep && delta > 0.0 {
+    delta -= 2.0 * PI;
+  }
+
+  let step = max_step(rx.max(ry)).max(0.01);
+  let steps = (delta.abs() / step).ceil().max(1.0) as usize;
+  let mut points = Vec::with_capacity(steps);
+  for i in 1..steps {
+    let angle = start_angle + delta * i as f32 / steps as f32;
+    let (x, y) = (rx * angle.cos(), ry * angle.sin());
+    points.push((center.0 + cos * x - sin * y, center.1 + sin * x + cos * y));
+  }
+  //End exactly at `to` so following commands connect without a gap.
+  points.push(to);
+  points
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::debug::assert_near;
+
+  #[test]
+  fn flatten_lines_and_subpaths() {
+    let subpaths = flatten(
+      &[
+        PathCommand::MoveTo(0.0, 0.0),
+        PathCommand::LineTo(1.0, 0.0),
+        PathCommand::LineTo(1.0, 1.0),
+        PathCommand::Close,
+        PathCommand::LineTo(0.0, 1.0),
+      ],
+      (2.0, 2.0),
+      (10.0, 10.0),
+      0.1,
+    );
+    assert_eq!(
+      subpaths,
+      vec![
+        (vec![(2.0, 2.0), (12.0, 2.0), (12.0, 12.0)], true),
+        //After closing, drawing continues from the start of the closed subpath.
+        (vec![(2.0, 2.0), (2.0, 12.0)], false),
+      ]
+    );
+  }
+  #[test]
+  fn curves_stay_within_tolerance() {
+    let quad = PathCommand::QuadTo {
+      control: (0.5, 1.0),
+      to: (1.0, 0.0),
+    };
+    let coarse = &flatten(&[quad], (0.0, 0.0), (20.0, 20.0), 1.0)[0].0;
+    let fine = &flatten(&[quad], (0.0, 0.0), (20.0, 20.0), 0.05)[0].0;
+    assert!(fine.len() > coarse.len());
+    //The curve is the parabola y = x * (2 - x / 10), chords sag below it by at most the tolerance.
+    for pair in fine.windows(2) {
+      let middle = ((pair[0].0 + pair[1].0) / 2.0, (pair[0].1 + pair[1].1) / 2.0);
+      let sag = middle.0 * (2.0 - middle.0 / 10.0) - middle.1;
+      assert!((0.0..=0.05 + 1e-3).contains(&sag), "{pair:?}");
+    }
+    assert_near(fine[0], (0.0, 0.0));
+    let cubic = PathCommand::CubicTo {
+      control_a: (0.0, 1.0),
+      control_b: (1.0, 1.0),
+      to: (1.0, 0.0),
+    };
+    let points = &flatten(&[cubic], (0.0, 0.0), (20.0, 20.0), 0.1)[0].0;
+    //Symmetric curve through (10, 15) at its middle.
+    assert!(points.iter().all(|p| p.1 <= 15.0 + 1e-4));
+    assert!(points.iter().any(|p| p.1 > 15.0 - 0.1));
+    assert_near(*points.last().unwrap(), (20.0, 0.0));
+    //Control points far off the sprite don't split the curve into billions of segments.
+    let wild = PathCommand::CubicTo {
+      control_a: (1e30, 0.0),
+      control_b: (1.0, 1.0),
+      to: (1.0, 0.0),
+    };
+    let points = &flatten(&[wild], (0.0, 0.0), (20.0, 20.0), 0.1)[0].0;
+    assert_eq!(points.len(), MAX_CURVE_STEPS as usize + 1);
+    assert_near(*points.last().unwrap(), (20.0, 0.0));
+  }
+  #[test]
+  fn arcs_follow_the_ellipse() {
+    //Half circles of radius 0.5 between (0, 0.5) and (1, 0.5), clockwise over the top with `sweep`.
+    let half_circle = |sweep: bool| {
+      let arc = PathCommand::ArcTo {
+        radii: (0.5, 0.5),
+        rotation: 0.0,
+        large_arc: false,
+        sweep,
+        to: (1.0, 0.5),
+      };
+      flatten(
+        &[PathCommand::MoveTo(0.0, 0.5), arc],
+        (0.0, 0.0),
+        (20.0, 20.0),
+        0.1,
+      )
+      .remove(0)
+      .0
+    };
+    let below = half_circle(false);
+    assert!(below
+      .iter()
+      .all(|p| ((p.0 - 10.0).hypot(p.1 - 10.0) - 10.0).abs() < 0.01));
+    assert!(below.iter().all(|p| p.1 >= 10.0 - 1e-4));
+    assert!(half_circle(true).iter().all(|p| p.1 <= 10.0 + 1e-4));
+    //Radii too small to reach the end are grown to a half circle.
+    let small = arc_points((0.0, 0.0), (2.0, 0.0), (0.1, 0.1), 0.0, false, true, |_| {
+      0.1
+    });
+    assert!(small
+      .iter()
+      .all(|p| ((p.0 - 1.0).hypot(p.1) - 1.0).abs() < 1e-4));
+  }
+}
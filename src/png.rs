@@ -0,0 +1,656 @@
+use std::{fs, io, path::Path};
+
+use crate::error::SpriteorError;
+
+const PNG_SIGNATURE: [u8; 8] = [137, 80, 78, 71, 13, 10, 26, 10];
+
+/** Rendering intent written to the sRGB chunk. */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SrgbIntent {
+  Perceptual = 0,
+  RelativeColorimetric = 1,
+  Saturation = 2,
+  AbsoluteColorimetric = 3,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PngOptions {
+  /** Writes an sRGB chunk with the given rendering intent. */
+  pub srgb: Option<SrgbIntent>,
+  /** Writes a gAMA chunk with the given image gamma, e.g. `1.0 / 2.2`. */
+  pub gamma: Option<f32>,
+}
+
+/**
+Encodes 8 bit RGBA values as a PNG file.
+
+Returns an error if `values` is not `width * height * 4` long.
+*/
+pub fn encode_png(
+  values: &[u8],
+  width: u16,
+  height: u16,
+  options: &PngOptions,
+) -> Result<Vec<u8>, SpriteorError> {
+  let row_length = width as usize * 4;
+  if values.len() != row_length * height as usize {
+    return Err(SpriteorError::InvalidValueCount {
+      width,
+      height,
+      count: values.len(),
+    });
+  }
+
+  let mut png = PNG_SIGNATURE.to_vec();
+
+  let mut ihdr = Vec::with_capacity(13);
+  ihdr.extend_from_slice(&(width as u32).to_be_bytes());
+  ihdr.extend_from_slice(&(height as u32).to_be_bytes());
+  //Bit depth 8, color type 6 (RGBA), deflate compression, adaptive filtering, no interlace.
+  ihdr.extend_from_slice(&[8, 6, 0, 0, 0]);
+  write_chunk(&mut png, b"IHDR", &ihdr);
+
+  if let Some(intent) = options.srgb {
+    write_chunk(&mut png, b"sRGB", &[intent as u8]);
+  }
+  if let Some(gamma) = options.gamma {
+    let gamma = (gamma * 100_000.0).round() as u32;
+    write_chunk(&mut png, b"gAMA", &gamma.to_be_bytes());
+  }
+
+  let filtered = filter_rows(values, row_length);
+  write_chunk(&mut png, b"IDAT", &zlib_compress(&filtered));
+  write_chunk(&mut png, b"IEND", &[]);
+  Ok(png)
+}
+
+pub(crate) fn write_png_file<P: AsRef<Path>>(
+  path: P,
+  values: &[u8],
+  width: u16,
+  height: u16,
+  options: &PngOptions,
+) -> io::Result<()> {
+  let png = encode_png(values, width, height, options)
+    .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+  fs::write(path, png)
+}
+
+fn write_chunk(png: &mut Vec<u8>, chunk_type: &[u8; 4], data: &[u8]) {
+  png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+  let crc_start = png.len();
+  png.extend_from_slice(chunk_type);
+  png.extend_from_slice(data);
+  let crc = crc32(&png[crc_start..]);
+  png.extend_from_slice(&crc.to_be_bytes());
+}
+
+//
+//
+//Row filtering.
+//
+
+/** Filters every row with the filter type giving the smallest sum of absolute values, same heuristic as libpng. */
+fn filter_rows(values: &[u8], row_length: usize) -> Vec<u8> {
+  let height = values.len() / row_length.max(1);
+  let mut filtered = Vec::with_capacity(values.len() + height);
+  let zero_row = vec![0_u8; row_length];
+  let mut candidate = vec![0_u8; row_length];
+  let mut best = vec![0_u8; row_length];
+
+  for y in 0..height {
+    let row = &values[y * row_length..(y + 1) * row_length];
+    let prev = if y > 0 {
+      &values[(y - 1) * row_length..y * row_length]
+    } else {
+      &zero_row[..]
+    };
+
+    let mut best_filter = 0;
+    let mut best_sum = u64::MAX;
+    for filter in 0..5_u8 {
+      for i in 0..row_length {
+        let a = if i >= 4 { row[i - 4] } else { 0 };
+        let b = prev[i];
+        let c = if i >= 4 { prev[i - 4] } else { 0 };
+        candidate[i] = row[i].wrapping_sub(match filter {
+          1 => a,
+          2 => b,
+          3 => ((a as u16 + b as u16) / 2) as u8,
+          4 => paeth(a, b, c),
+          _ => 0,
+        });
+      }
+      let sum = candidate
+        .iter()
+        .map(|&v| (v as i8).unsigned_abs() as u64)
+        .sum();
+      if sum < best_sum {
+        best_sum = sum;
+        best_filter = filter;
+        best.copy_from_slice(&candidate);
+      }
+    }
+    filtered.push(best_filter);
+    filtered.extend_from_slice(&best);
+  }
+  filtered
+}
+
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+  let p = a as i16 + b as i16 - c as i16;
+  let pa = (p - a as i16).abs();
+  let pb = (p - b as i16).abs();
+  let pc = (p - c as i16).abs();
+  if pa <= pb && pa <= pc {
+    a
+  } else if pb <= pc {
+    b
+  } else {
+    c
+  }
+}
+
+//
+//
+//Checksums.
+//
+
+const fn crc32_table() -> [u32; 256] {
+  let mut table = [0_u32; 256];
+  let mut n = 0;
+  while n < 256 {
+    let mut c = n as u32;
+    let mut k = 0;
+    while k < 8 {
+      c = if c & 1 != 0 {
+        0xEDB8_8320 ^ (c >> 1)
+      } else {
+        c >> 1
+      };
+      k += 1;
+    }
+    table[n] = c;
+    n += 1;
+  }
+  table
+}
+const CRC32_TABLE: [u32; 256] = crc32_table();
+
+pub(crate) fn crc32(data: &[u8]) -> u32 {
+  let mut crc = 0xFFFF_FFFF_u32;
+  for &byte in data {
+    crc = CRC32_TABLE[((crc ^ byte as u32) & 0xFF) as usize] ^ (crc >> 8);
+  }
+  crc ^ 0xFFFF_FFFF
+}
+
+pub(crate) fn adler32(data: &[u8]) -> u32 {
+  const MOD_ADLER: u32 = 65521;
+  let mut a = 1_u32;
+  let mut b = 0_u32;
+  //5552 is the largest block that cannot overflow b before taking the modulo.
+  for block in data.chunks(5552) {
+    for &byte in block {
+      a += byte as u32;
+      b += a;
+    }
+    a %= MOD_ADLER;
+    b %= MOD_ADLER;
+  }
+  (b << 16) | a
+}
+
+//
+//
+//Deflate, LZ77 matching encoded with the fixed Huffman codes from RFC 1951.
+//
+
+const WINDOW_SIZE: usize = 32768;
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+const MAX_CHAIN: usize = 128;
+const HASH_BITS: usize = 15;
+
+const LENGTH_BASE: [u16; 29] = [
+  3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+  163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+  0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DISTANCE_BASE: [u16; 30] = [
+  1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537, 2049,
+  3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] = [
+  0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13,
+];
+
+struct BitWriter {
+  bytes: Vec<u8>,
+  bit_buffer: u32,
+  bit_count: u8,
+}
+impl BitWriter {
+  fn new() -> BitWriter {
+    BitWriter {
+      bytes: Vec::new(),
+      bit_buffer: 0,
+      bit_count: 0,
+    }
+  }
+  /** Writes the lowest `count` bits of `bits`, least significant bit first. */
+  fn write_bits(&mut self, bits: u32, count: u8) {
+    self.bit_buffer |= bits << self.bit_count;
+    self.bit_count += count;
+    while self.bit_count >= 8 {
+      self.bytes.push(self.bit_buffer as u8);
+      self.bit_buffer >>= 8;
+      self.bit_count -= 8;
+    }
+  }
+  /** Huffman codes are packed starting with their most significant bit. */
+  fn write_code(&mut self, code: u32, length: u8) {
+    let mut reversed = 0;
+    for i in 0..length {
+      reversed |= ((code >> i) & 1) << (length - 1 - i);
+    }
+    self.write_bits(reversed, length);
+  }
+  fn finish(mut self) -> Vec<u8> {
+    if self.bit_count > 0 {
+      self.bytes.push(self.bit_buffer as u8);
+    }
+    self.bytes
+  }
+}
+
+fn write_fixed_literal(writer: &mut BitWriter, symbol: u16) {
+  let symbol = symbol as u32;
+  match symbol {
+    0..=143 => writer.write_code(0x30 + symbol, 8),
+    144..=255 => writer.write_code(0x190 + symbol - 144, 9),
+    256..=279 => writer.write_code(symbol - 256, 7),
+    _ => writer.write_code(0xC0 + symbol - 280, 8),
+  }
+}
+
+fn write_match(writer: &mut BitWriter, length: usize, distance: usize) {
+  let length_index = LENGTH_BASE
+    .iter()
+    .rposition(|&base| base as usize <= length)
+    .unwrap_or(0);
+  write_fixed_literal(writer, 257 + length_index as u16);
+  writer.write_bits(
+    (length - LENGTH_BASE[length_index] as usize) as u32,
+    LENGTH_EXTRA[length_index],
+  );
+
+  let distance_index = DISTANCE_BASE
+    .iter()
+    .rposition(|&base| base as usize <= distance)
+    .unwrap_or(0);
+  writer.write_code(distance_index as u32, 5);
+  writer.write_bits(
+    (distance - DISTANCE_BASE[distance_index] as usize) as u32,
+    DISTANCE_EXTRA[distance_index],
+  );
+}
+
+fn hash3(data: &[u8], i: usize) -> usize {
+  let v = (data[i] as u32) << 16 | (data[i + 1] as u32) << 8 | data[i + 2] as u32;
+  (v.wrapping_mul(2_654_435_761) >> (32 - HASH_BITS)) as usize
+}
+
+/** Raw deflate stream (RFC 1951) as a single final block using fixed Huffman codes. */
+pub(crate) fn deflate(data: &[u8]) -> Vec<u8> {
+  let mut writer = BitWriter::new();
+  //BFINAL = 1, BTYPE = 01 (fixed Huffman).
+  writer.write_bits(1, 1);
+  writer.write_bits(1, 2);
+
+  let mut head = vec![usize::MAX; 1 << HASH_BITS];
+  let mut prev = vec![usize::MAX; WINDOW_SIZE];
+  let insert = |head: &mut Vec<usize>, prev: &mut Vec<usize>, i: usize| {
+    if i + MIN_MATCH <= data.len() {
+      let h = hash3(data, i);
+      prev[i % WINDOW_SIZE] = head[h];
+      head[h] = i;
+    }
+  };
+
+  let mut i = 0;
+  while i < data.len() {
+    let mut best_length = 0;
+    let mut best_distance = 0;
+    if i + MIN_MATCH <= data.len() {
+      let max_length = MAX_MATCH.min(data.len() - i);
+      let mut candidate = head[hash3(data, i)];
+      let mut chain = 0;
+      while candidate != usize::MAX && i - candidate <= WINDOW_SIZE && chain < MAX_CHAIN {
+        let mut length = 0;
+        while length < max_length && data[candidate + length] == data[i + length] {
+          length += 1;
+        }
+        if length > best_length {
+          best_length = length;
+          best_distance = i - candidate;
+          if length == max_length {
+            break;
+          }
+        }
+        let next = prev[candidate % WINDOW_SIZE];
+        //Stale entries from earlier windows point forward, stop following them.
+        if next == usize::MAX || next >= candidate {
+          break;
+        }
+        candidate = next;
+        chain += 1;
+      }
+    }
+
+    if best_length >= MIN_MATCH {
+      write_match(&mut writer, best_length, best_distance);
+      for j in i..i + best_length {
+        insert(&mut head, &mut prev, j);
+      }
+      i += best_length;
+    } else {
+      write_fixed_literal(&mut writer, data[i] as u16);
+      insert(&mut head, &mut prev, i);
+      i += 1;
+    }
+  }
+  write_fixed_literal(&mut writer, 256);
+  writer.finish()
+}
+
+/** Deflate stream wrapped in a zlib header and Adler-32 trailer (RFC 1950). */
+pub(crate) fn zlib_compress(data: &[u8]) -> Vec<u8> {
+  //CM = 8 (deflate) with a 32K window, default compression level, check bits make the header divisible by 31.
+  let mut out = vec![0x78, 0x9C];
+  out.extend_from_slice(&deflate(data));
+  out.extend_from_slice(&adler32(data).to_be_bytes());
+  out
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /** Minimal inflate supporting the stored and fixed Huffman blocks this encoder produces. */
+  fn inflate(data: &[u8]) -> Vec<u8> {
+    struct BitReader<'a> {
+      data: &'a [u8],
+      position: usize,
+    }
+    impl BitReader<'_> {
+      fn bit(&mut self) -> u32 {
+        let bit = (self.data[self.position / 8] >> (self.position % 8)) & 1;
+        self.position += 1;
+        bit as u32
+      }
+      fn bits(&mut self, count: u8) -> u32 {
+        (0..count).fold(0, |v, i| v | (self.bit() << i))
+      }
+      fn code(&mut self, length: u8) -> u32 {
+        (0..length).fold(0, |v, _| (v << 1) | self.bit())
+      }
+    }
+    fn fixed_literal(reader: &mut BitReader) -> u16 {
+      let code = reader.code(7);
+      if code <= 0x17 {
+        return (code + 256) as u16;
+      }
+      let code = (code << 1) | reader.bit();
+      if (0x30..=0xBF).contains(&code) {
+        return (code - 0x30) as u16;
+      }
+      if (0xC0..=0xC7).contains(&code) {
+        return (code - 0xC0 + 280) as u16;
+      }
+      let code = (code << 1) | reader.bit();
+      (code - 0x190 + 144) as u16
+    }
+
+    let mut reader = BitReader { data, position: 0 };
+    let mut out: Vec<u8> = Vec::new();
+    loop {
+      let last = reader.bit();
+      match reader.bits(2) {
+        0 => {
+          reader.position = reader.position.div_ceil(8) * 8;
+          let length = reader.bits(16) as usize;
+          let _ = reader.bits(16);
+          for _ in 0..length {
+            out.push(reader.bits(8) as u8);
+          }
+        }
+        1 => loop {
+          let symbol = fixed_literal(&mut reader);
+          if symbol < 256 {
+            out.push(symbol as u8);
+          } else if symbol == 256 {
+            break;
+          } else {
+            let index = (symbol - 257) as usize;
+            let length = LENGTH_BASE[index] as usize + reader.bits(LENGTH_EXTRA[index]) as usize;
+            let index = reader.code(5) as usize;
+            let distance =
+              DISTANCE_BASE[index] as usize + reader.bits(DISTANCE_EXTRA[index]) as usize;
+            let start = out.len() - distance;
+            for j in 0..length {
+              out.push(out[start + j]);
+            }
+          }
+        },
+        block_type => panic!("Unsupported block type {}.", block_type),
+      }
+      if last == 1 {
+        break;
+      }
+    }
+    out
+  }
+
+  struct DecodedPng {
+    width: u32,
+    height: u32,
+    chunk_types: Vec<String>,
+    srgb: Option<u8>,
+    gamma: Option<u32>,
+    values: Vec<u8>,
+  }
+
+  fn decode_png(png: &[u8]) -> DecodedPng {
+    assert_eq!(png[0..8], PNG_SIGNATURE);
+    let mut decoded = DecodedPng {
+      width: 0,
+      height: 0,
+      chunk_types: Vec::new(),
+      srgb: None,
+      gamma: None,
+      values: Vec::new(),
+    };
+    let mut idat = Vec::new();
+    let mut i = 8;
+    while i < png.len() {
+      let length = u32::from_be_bytes(png[i..i + 4].try_into().unwrap()) as usize;
+      let chunk_type = &png[i + 4..i + 8];
+      let data = &png[i + 8..i + 8 + length];
+      let crc = u32::from_be_bytes(png[i + 8 + length..i + 12 + length].try_into().unwrap());
+      assert_eq!(crc, crc32(&png[i + 4..i + 8 + length]));
+      decoded
+        .chunk_types
+        .push(String::from_utf8(chunk_type.to_vec()).unwrap());
+      match chunk_type {
+        b"IHDR" => {
+          decoded.width = u32::from_be_bytes(data[0..4].try_into().unwrap());
+          decoded.height = u32::from_be_bytes(data[4..8].try_into().unwrap());
+          assert_eq!(data[8..13], [8, 6, 0, 0, 0]);
+        }
+        b"sRGB" => decoded.srgb = Some(data[0]),
+        b"gAMA" => decoded.gamma = Some(u32::from_be_bytes(data.try_into().unwrap())),
+        b"IDAT" => idat.extend_from_slice(data),
+        _ => (),
+      }
+      i += 12 + length;
+    }
+
+    assert_eq!((idat[0] as u16 * 256 + idat[1] as u16) % 31, 0);
+    let filtered = inflate(&idat[2..idat.len() - 4]);
+    let adler = u32::from_be_bytes(idat[idat.len() - 4..].try_into().unwrap());
+    assert_eq!(adler, adler32(&filtered));
+
+    let row_length = decoded.width as usize * 4;
+    let mut values: Vec<u8> = Vec::with_capacity(row_length * decoded.height as usize);
+    for y in 0..decoded.height as usize {
+      let filter = filtered[y * (row_length + 1)];
+      let row = &filtered[y * (row_length + 1) + 1..(y + 1) * (row_length + 1)];
+      let row_start = values.len();
+      for (x, &v) in row.iter().enumerate() {
+        let a = if x >= 4 { values[row_start + x - 4] } else { 0 };
+        let b = if y > 0 {
+          values[row_start + x - row_length]
+        } else {
+          0
+        };
+        let c = if y > 0 && x >= 4 {
+          values[row_start + x - row_length - 4]
+        } else {
+          0
+        };
+        values.push(v.wrapping_add(match filter {
+          0 => 0,
+          1 => a,
+          2 => b,
+          3 => ((a as u16 + b as u16) / 2) as u8,
+          4 => paeth(a, b, c),
+          _ => panic!("Invalid filter type {}.", filter),
+        }));
+      }
+    }
+    decoded.values = values;
+    decoded
+  }
+
+  /** Deterministic noise so the tests do not need a random crate. */
+  fn noise(len: usize, seed: u32) -> Vec<u8> {
+    let mut state = seed;
+    (0..len)
+      .map(|_| {
+        state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+        (state >> 16) as u8
+      })
+      .collect()
+  }
+
+  #[test]
+  fn crc32_check_value() {
+    assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    assert_eq!(crc32(b"IEND"), 0xAE42_6082);
+  }
+  #[test]
+  fn adler32_check_value() {
+    assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+    assert_eq!(adler32(&[]), 1);
+  }
+  #[test]
+  fn adler32_large_input() {
+    let data = vec![255_u8; 100_000];
+    let mut a = 1_u64;
+    let mut b = 0_u64;
+    for &v in &data {
+      a = (a + v as u64) % 65521;
+      b = (b + a) % 65521;
+    }
+    assert_eq!(adler32(&data), ((b << 16) | a) as u32);
+  }
+
+  #[test]
+  fn deflate_round_trip_empty() {
+    assert_eq!(inflate(&deflate(&[])), Vec::<u8>::new());
+  }
+  #[test]
+  fn deflate_round_trip_repeating() {
+    let data: Vec<u8> = (0..70_000).map(|i| (i % 7) as u8).collect();
+    let compressed = deflate(&data);
+    assert!(compressed.len() < data.len() / 20);
+    assert_eq!(inflate(&compressed), data);
+  }
+  #[test]
+  fn deflate_round_trip_noise() {
+    let data = noise(50_000, 7);
+    assert_eq!(inflate(&deflate(&data)), data);
+  }
+  #[test]
+  fn deflate_round_trip_long_distance() {
+    let mut data = noise(40_000, 3);
+    let copy = data[100..400].to_vec();
+    data.extend_from_slice(&copy);
+    assert_eq!(inflate(&deflate(&data)), data);
+  }
+
+  #[test]
+  fn png_round_trip_noise() {
+    let values = noise(13 * 9 * 4, 42);
+    let decoded = decode_png(&encode_png(&values, 13, 9, &PngOptions::default()).unwrap());
+    assert_eq!((decoded.width, decoded.height), (13, 9));
+    assert_eq!(decoded.values, values);
+  }
+  #[test]
+  fn png_round_trip_gradient() {
+    let values: Vec<u8> = (0..64 * 64)
+      .flat_map(|i| [(i % 64 * 4) as u8, (i / 64 * 4) as u8, 128, 255])
+      .collect();
+    let png = encode_png(&values, 64, 64, &PngOptions::default()).unwrap();
+    assert!(png.len() < values.len() / 4);
+    assert_eq!(decode_png(&png).values, values);
+  }
+  #[test]
+  fn png_chunk_order_without_options() {
+    let decoded = decode_png(&encode_png(&[0; 8 * 8 * 4], 8, 8, &PngOptions::default()).unwrap());
+    assert_eq!(decoded.chunk_types, vec!["IHDR", "IDAT", "IEND"]);
+    assert_eq!(decoded.srgb, None);
+    assert_eq!(decoded.gamma, None);
+  }
+  #[test]
+  fn png_chunk_order_with_options() {
+    let options = PngOptions {
+      srgb: Some(SrgbIntent::RelativeColorimetric),
+      gamma: Some(1.0 / 2.2),
+    };
+    let decoded = decode_png(&encode_png(&[0; 8 * 8 * 4], 8, 8, &options).unwrap());
+    assert_eq!(
+      decoded.chunk_types,
+      vec!["IHDR", "sRGB", "gAMA", "IDAT", "IEND"]
+    );
+    assert_eq!(decoded.srgb, Some(1));
+    assert_eq!(decoded.gamma, Some(45455));
+  }
+  #[test]
+  fn png_single_red_pixel() {
+    //Checked against zlib and a standalone CRC-32, the IDAT holds filter 0 followed by the pixel.
+    #[rustfmt::skip]
+    let expected = [
+      137, 80, 78, 71, 13, 10, 26, 10,
+      0, 0, 0, 13, 73, 72, 68, 82, 0, 0, 0, 1, 0, 0, 0, 1, 8, 6, 0, 0, 0, 31, 21, 196, 137,
+      0, 0, 0, 13, 73, 68, 65, 84, 120, 156, 99, 248, 207, 192, 240, 31, 0, 5, 0, 1, 255, 137, 153, 61, 29,
+      0, 0, 0, 0, 73, 69, 78, 68, 174, 66, 96, 130,
+    ];
+    let png = encode_png(&[255, 0, 0, 255], 1, 1, &PngOptions::default()).unwrap();
+    assert_eq!(png, expected);
+  }
+  #[test]
+  fn png_wrong_value_count() {
+    assert_eq!(
+      encode_png(&[0; 10], 8, 8, &PngOptions::default()),
+      Err(SpriteorError::InvalidValueCount {
+        width: 8,
+        height: 8,
+        count: 10
+      })
+    );
+  }
+}
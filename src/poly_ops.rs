@@ -1,135 +1,559 @@
+use std::{f32::consts::PI, iter::once};
+
 use crate::{
-  colors::add_color_set_pixel,
-  debug::print_points,
-  maths::{poly_contains, ContainsResult},
-  rect_ops::RectOpUnw,
+  border_style::{StrokeAlign, StrokeJoin},
+  colors::{add_color_set_pixel, mix_color, scale_alpha},
+  error::{check_coordinate, SpriteorError},
+  maths::{distance_to_outline, xy_to_i, MITRE_LIMIT},
+  paint::Paint,
+  raster::{contour_segments, near_segments, poly_spans, FillRule},
+  rect_ops::{resolve_area, RectOpUnw},
 };
 
-fn scale_poly(x: f32, y: f32, pts: &Vec<(f32, f32)>) -> Vec<(f32, f32)> {
-  pts
-    .iter()
-    // .map(|p| ((p.0 * x).ceil(), (p.1 * y).ceil()))
-    .map(|p| ((p.0 * x).floor(), (p.1 * y).floor()))
-    // .map(|p| ((p.0 * x).round(), (p.1 * y).round()))
-    .collect()
+/** How the unit square of a polygon or path is scaled to the area it is drawn in, like CSS `object-fit`. */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PolyFit {
+  /** The polygon fills the whole tile, so it is distorted in tiles that are not square. */
+  #[default]
+  Stretch,
+  /** The polygon keeps its aspect ratio and is as large as fits in the tile. */
+  Contain,
+  /** The polygon keeps its aspect ratio and covers the whole tile, the parts outside of the tile are cut off. */
+  Cover,
+}
+impl PolyFit {
+  /** Scale (x, y) and offset (x, y) in pixels that place the unit square in a `width` x `height` area, `align` positions it where it does not fill the area exactly. */
+  pub(crate) fn place(
+    &self,
+    align: (f32, f32),
+    width: f32,
+    height: f32,
+  ) -> ((f32, f32), (f32, f32)) {
+    let size = match self {
+      PolyFit::Stretch => return ((width, height), (0.0, 0.0)),
+      PolyFit::Contain => width.min(height),
+      PolyFit::Cover => width.max(height),
+    };
+    (
+      (size, size),
+      ((width - size) * align.0, (height - size) * align.1),
+    )
+  }
+}
+
+/** Which tiles of a poly op are moved by half a tile, for brick and hex patterns. */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TileStagger {
+  #[default]
+  None,
+  /** Every other row starting from the second is moved right by half a tile and gap, like bricks. */
+  Rows,
+  /** Every other column starting from the second is moved down by half a tile and gap, like hexagons with flat tops. */
+  Columns,
+}
+
+/**
+Rotation, mirroring, scaling and moving of a polygon in its unit square before it is fitted to a tile.
+
+Flipping, scaling and rotation are around the center (0.5, 0.5) of the unit square, in that order, then the polygon is moved by `translate`.
+*/
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PolyTransform {
+  /** Clockwise rotation in degrees. */
+  pub rotation: f32,
+  /** Mirrors the polygon left to right. */
+  pub flip_x: bool,
+  /** Mirrors the polygon top to bottom. */
+  pub flip_y: bool,
+  /** Scale (x, y), 1 keeps the size. */
+  pub scale: (f32, f32),
+  /** Distance (x, y) the polygon is moved by in tiles, (0.5, 0) moves it right by half a tile. */
+  pub translate: (f32, f32),
+}
+impl PolyTransform {
+  pub(crate) fn apply(&self, point: &(f32, f32)) -> (f32, f32) {
+    let mut x = (point.0 - 0.5) * self.scale.0;
+    let mut y = (point.1 - 0.5) * self.scale.1;
+    if self.flip_x {
+      x = -x;
+    }
+    if self.flip_y {
+      y = -y;
+    }
+    let (sin, cos) = self.rotation.to_radians().sin_cos();
+    (
+      x * cos - y * sin + 0.5 + self.translate.0,
+      x * sin + y * cos + 0.5 + self.translate.1,
+    )
+  }
+}
+impl Default for PolyTransform {
+  fn default() -> Self {
+    PolyTransform {
+      rotation: 0.0,
+      flip_x: false,
+      flip_y: false,
+      scale: (1.0, 1.0),
+      translate: (0.0, 0.0),
+    }
+  }
+}
+
+/** Which tiles of a poly op use the `alternate_transform`. */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TileAlternation {
+  /** Every other tile in both directions, like the dark squares of a chessboard. */
+  #[default]
+  Checkerboard,
+  /** Every other row starting from the second. */
+  Rows,
+  /** Every other column starting from the second. */
+  Columns,
 }
 
 #[derive(Debug, Clone)]
 pub struct SpriteorPolyOp {
+  /**
+  Coordinates (x, y) of the area the tiles are placed in. Must be in range [-4096, 4096]. Negative values are counted from right-edge for x and bottom for y.
+
+  Works like `point_a` of [`SpriteorRectOp`](crate::SpriteorRectOp), defaults to the whole container.
+  */
+  pub point_a: Option<(i16, i16)>,
+  /** Opposite corner of the area from `point_a`, works like `point_b` of [`SpriteorRectOp`](crate::SpriteorRectOp). */
+  pub point_b: Option<(i16, i16)>,
   pub x_count: u16,
   pub y_count: u16,
+  /** Pixels (x, y) between neighbouring tiles. */
+  pub tile_gap: (u16, u16),
+  /** Pixels between the edges of the area and the tiles. */
+  pub padding: u16,
+  /** Centers the tiles in the area. By default the pixels left over when the area does not divide evenly into tiles are on the right and bottom. */
+  pub center_tiles: bool,
+  /**
+  Moves every other row or column of tiles by half a tile. Defaults to none.
+
+  The rows or columns are filled up with cut off tiles at both ends, and tiles of a staggered layout are cut off where the rows or columns end instead of reaching past them.
+  */
+  pub stagger: TileStagger,
+  /** Points in range [0, 1] that are scaled to the size of a tile. */
   pub polygon: Vec<(f32, f32)>,
+  /**
+  More closed outlines in the same unit square, drawn together with `polygon` as one shape with borders along every outline.
+
+  Outlines inside of others are holes with even-odd, with non-zero only if they go around the other way. Outlines next to each other are separate parts.
+  */
+  pub contours: Vec<Vec<(f32, f32)>>,
+  /** Decides what is inside when the polygon overlaps itself or its `contours`. Defaults to even-odd. */
+  pub fill_rule: FillRule,
+  /** Rotation, mirroring, scaling and moving of the polygon in every tile. Defaults to none. */
+  pub transform: PolyTransform,
+  /** Transform used instead of `transform` in every other tile, for example rotated by 180 degrees for chevrons. */
+  pub alternate_transform: Option<PolyTransform>,
+  /** Which tiles use the `alternate_transform`. Defaults to checkerboard. */
+  pub alternate_tiles: TileAlternation,
+  /** How the polygon is scaled to each tile. Defaults to stretch. */
+  pub fit: PolyFit,
+  /** Position (x, y) of the polygon in range [0, 1] when `fit` leaves space around it or cuts it off, (0, 0) is the top left of the tile. Defaults to centered. */
+  pub align: (f32, f32),
+  /** Width of the border in pixels, measured from the nearest edge of the outline. */
   pub border_thickness: u8,
-  pub border_color: Option<[u8; 4]>,
-  pub fill_color: Option<[u8; 4]>,
+  /** Where the border is drawn relative to the outline. Defaults to inside, center and outside borders can reach past the tile into its neighbours and the sprite margin. */
+  pub stroke_align: StrokeAlign,
+  /** Shape of the border at the corners of the polygon. Defaults to mitre. */
+  pub stroke_join: StrokeJoin,
+  /** Paint of the polygon outline, gradients span the whole area the tiles are placed in. Defaults to white. */
+  pub border_color: Option<Paint>,
+  /** Paint inside of the polygon, gradients span the whole area the tiles are placed in. Defaults to light gray. */
+  pub fill_color: Option<Paint>,
+  /** Smooths the edges of the polygon and its border with partially transparent pixels. Disable for pixel art styles. Defaults to true. */
+  pub anti_alias: bool,
 }
 impl SpriteorPolyOp {
-  pub(crate) fn add_to(&self, values: &mut Vec<u8>, container: &RectOpUnw) {
-    let tile_width = (container.border_box_right - container.border_box_left + 1) / self.x_count;
-    let tile_height = (container.border_box_bottom - container.border_box_top + 1) / self.y_count;
-    println!(
-      "tile_width:{}  tile_height:{}  xc:{}    {} - {}",
-      tile_width, tile_height, self.x_count, container.border_box_right, container.border_box_left
+  pub(crate) fn validate(&self) -> Result<(), SpriteorError> {
+    if self.x_count == 0 || self.y_count == 0 {
+      return Err(SpriteorError::ZeroTileCount {
+        x_count: self.x_count,
+        y_count: self.y_count,
+      });
+    }
+    for point in [self.point_a, self.point_b].iter().flatten() {
+      check_coordinate(point.0 as i32, point.1 as i32)?;
+    }
+    Ok(())
+  }
+  pub(crate) fn add_to(&self, values: &mut [u8], container: &RectOpUnw, sprite_width: &u16) {
+    let (top, right, bottom, left) = resolve_area(
+      self.point_a,
+      self.point_b,
+      (
+        container.border_box_top,
+        container.border_box_right,
+        container.border_box_bottom,
+        container.border_box_left,
+      ),
     );
+    let (Some((first_x, tile_width)), Some((first_y, tile_height))) = (
+      self.tile_layout(left, right, self.x_count, self.tile_gap.0),
+      self.tile_layout(top, bottom, self.y_count, self.tile_gap.1),
+    ) else {
+      return;
+    };
 
-    let poly = scale_poly(
-      tile_width as f32,
-      tile_height as f32,
-      // tile_width as f32 - 1.0,
-      // tile_height as f32 - 1.0,
-      &self.polygon,
+    let fill_color = self
+      .fill_color
+      .clone()
+      .unwrap_or(Paint::Solid([200, 200, 200, 255]));
+    let border_color = self
+      .border_color
+      .clone()
+      .unwrap_or(Paint::Solid([255, 255, 255, 255]));
+
+    //Rasterize a single tile for each transform and set the same pixels in every tile.
+    let pixels = self.tile_pixels(&self.transform, tile_width, tile_height);
+    let alternate_pixels = self
+      .alternate_transform
+      .map(|transform| self.tile_pixels(&transform, tile_width, tile_height));
+
+    //Top left of every tile, staggered layouts get an extra cut off tile at the start of the moved rows or columns.
+    let pitch = (
+      tile_width as i32 + self.tile_gap.0 as i32,
+      tile_height as i32 + self.tile_gap.1 as i32,
     );
-    print_points(&"poly", &poly);
-
-    let start_pixel_row = container.border_box_top;
-    let start_pixel_on_row = container.border_box_left;
-    let pixels_per_row = container.border_box_right - container.border_box_left + 1;
-    let start_pixel = (pixels_per_row * start_pixel_row) + start_pixel_on_row;
-
-    //
-    //Calculate index offsets for each tile.
-
-    //Top left pixel index of tiles.
-    let mut tile_index_offsets: Vec<usize> = Vec::new();
-    tile_index_offsets.reserve((self.x_count * self.y_count) as usize);
-    let mut i = start_pixel;
-    for y in 0..self.y_count {
-      for _ in 0..self.x_count {
-        // println!("i:{}   offset:{}", tile_index_offsets.len(), i);
-        tile_index_offsets.push(i as usize);
-        //Move to next tile.
-        i += tile_width;
+    let mut origins: Vec<(i32, i32, bool)> = Vec::new();
+    for tile_row in -1..self.y_count as i32 {
+      for tile_column in -1..self.x_count as i32 {
+        let mut origin = (
+          first_x + tile_column * pitch.0,
+          first_y + tile_row * pitch.1,
+        );
+        match self.stagger {
+          TileStagger::Rows if tile_row >= 0 && tile_row % 2 == 1 => origin.0 += pitch.0 / 2,
+          TileStagger::Columns if tile_column >= 0 && tile_column % 2 == 1 => {
+            origin.1 += pitch.1 / 2
+          }
+          _ if tile_row < 0 || tile_column < 0 => continue,
+          _ => {}
+        }
+        let alternate = match self.alternate_tiles {
+          TileAlternation::Checkerboard => (tile_row + tile_column).rem_euclid(2) == 1,
+          TileAlternation::Rows => tile_row.rem_euclid(2) == 1,
+          TileAlternation::Columns => tile_column.rem_euclid(2) == 1,
+        };
+        origins.push((origin.0, origin.1, alternate));
       }
-      //Move index to next tile row.
-      i = (pixels_per_row * start_pixel_row) + (pixels_per_row * y) + start_pixel_on_row;
     }
 
-    let fill_color = self.border_color.unwrap_or([200, 200, 200, 255]);
-    let border_color = self.border_color.unwrap_or([255, 255, 255, 255]);
-
-    //
-    //
-    //Loop a tile and for every index offset set the value returned by the poly_contains check.
-    for tile_y in 0..tile_height {
-      for tile_x in 0..tile_width {
-        let p = (tile_x, tile_y);
-        let contains = poly_contains(&poly, &p, self.border_thickness);
-
-        // println!("tx:{} ty:{}       {:?}", tile_x, tile_y, contains);
-
-        if contains == ContainsResult::Outside {
+    //Staggered tiles are cut off at the ends of the rows or columns, everything else only at the sprite edges.
+    let sprite_height = (values.len() / 4 / *sprite_width as usize) as i32;
+    let grid_end =
+      |first: i32, count: u16, pitch: i32, gap: u16| first + count as i32 * pitch - gap as i32;
+    let (mut clip_x, mut clip_y) = (0..*sprite_width as i32, 0..sprite_height);
+    match self.stagger {
+      TileStagger::Rows => {
+        clip_x =
+          first_x.max(0)..grid_end(first_x, self.x_count, pitch.0, self.tile_gap.0).min(clip_x.end)
+      }
+      TileStagger::Columns => {
+        clip_y =
+          first_y.max(0)..grid_end(first_y, self.y_count, pitch.1, self.tile_gap.1).min(clip_y.end)
+      }
+      TileStagger::None => {}
+    }
+    for (origin_x, origin_y, alternate) in origins {
+      let pixels = match (alternate, &alternate_pixels) {
+        (true, Some(alternate_pixels)) => alternate_pixels,
+        _ => &pixels,
+      };
+      let runs = pixels
+        .runs
+        .iter()
+        .flat_map(|(y, x_start, x_end)| (*x_start..=*x_end).map(move |x| (x, *y, 1.0, 1.0)));
+      for (tile_x, tile_y, coverage, fill) in runs.chain(pixels.edge.iter().copied()) {
+        let (x, y) = (origin_x + tile_x, origin_y + tile_y);
+        if !clip_x.contains(&x) || !clip_y.contains(&y) {
           continue;
         }
-        let color: &[u8; 4] = match contains {
-          ContainsResult::Inside => &fill_color,
-          ContainsResult::Border => &border_color,
-          _ => &[0, 0, 0, 0],
+        let (x, y) = (x as u16, y as u16);
+        let paint_at = |paint: &Paint| paint.color_at(&x, &y, &left, &top, &right, &bottom);
+        let color = if fill >= 1.0 {
+          paint_at(&fill_color)
+        } else if fill <= 0.0 {
+          paint_at(&border_color)
+        } else {
+          mix_color(&paint_at(&border_color), &paint_at(&fill_color), fill)
         };
-        for offset in &tile_index_offsets {
-          let index = offset + tile_x as usize + (tile_y as usize * pixels_per_row as usize);
-          // println!("tx:{} ty:{}   t:{}   idx:{}", tile_x, tile_y, offset, index);
-          add_color_set_pixel(values, &index, &color)
-          // set_pixel(&mut quarter_pixels, &idx, &color);
-        }
+        add_color_set_pixel(
+          values,
+          &xy_to_i(sprite_width, &x, &y),
+          &scale_alpha(&color, coverage),
+        );
       }
     }
   }
+
+  /** Pixels of the polygon with `transform` in a tile, relative to the top left of the tile. */
+  fn tile_pixels(
+    &self,
+    transform: &PolyTransform,
+    tile_width: u16,
+    tile_height: u16,
+  ) -> TilePixels {
+    let (scale, offset) = self
+      .fit
+      .place(self.align, tile_width as f32, tile_height as f32);
+    let contours: Vec<Vec<(f32, f32)>> = once(&self.polygon)
+      .chain(&self.contours)
+      .map(|contour| {
+        contour
+          .iter()
+          .map(|p| transform.apply(p))
+          .map(|p| (offset.0 + p.0 * scale.0, offset.1 + p.1 * scale.1))
+          .map(|p| {
+            //Hard edged fills put every point on the pixel it falls in like the ray casting did, borders need the exact outline to keep their width.
+            if self.anti_alias || self.border_thickness > 0 {
+              p
+            } else {
+              (p.0.floor() + 0.5, p.1.floor() + 0.5)
+            }
+          })
+          .collect()
+      })
+      .collect();
+    let (inside_thickness, outside_thickness) =
+      self.stroke_align.split(self.border_thickness as u16);
+    //Covering polygons are cut off at the tile edges, borders and smoothed edges included.
+    let reach = if self.fit == PolyFit::Cover {
+      0
+    } else {
+      outside_thickness as i32 + self.anti_alias as i32
+    };
+    //Only pixels this close to the outline are on the border or partly covered, mitres reach further at sharp corners.
+    let thickness = inside_thickness.max(outside_thickness) as f32;
+    let near_reach = if self.stroke_join == StrokeJoin::Mitre {
+      thickness * MITRE_LIMIT
+    } else {
+      thickness
+    } + 1.0;
+    let thickness = (inside_thickness as f32, outside_thickness as f32);
+    outline_pixels(
+      &contours,
+      (tile_width, tile_height),
+      self.fill_rule,
+      reach,
+      (self.anti_alias || self.border_thickness > 0).then_some(near_reach),
+      |inside, distance| {
+        edge_coverage(
+          inside,
+          distance,
+          thickness,
+          self.stroke_join,
+          self.anti_alias,
+        )
+      },
+    )
+  }
+
+  /**
+  Start of the first tile and the size of the tiles along one axis of the area from `start` to `end`, None if the tiles do not fit.
+
+  The tiles are placed inside of the `padding` with `gap` pixels between them, the pixels left over are split around them if `center_tiles` is set.
+  */
+  fn tile_layout(&self, start: u16, end: u16, count: u16, gap: u16) -> Option<(i32, u16)> {
+    //In i64 since many tiles with wide gaps can take up more than fits in i32.
+    let available =
+      (end - start + 1) as i64 - self.padding as i64 * 2 - (count as i64 - 1) * gap as i64;
+    let size = available / count as i64;
+    if size <= 0 {
+      return None;
+    }
+    let left_over = available - size * count as i64;
+    let first =
+      start as i64 + self.padding as i64 + if self.center_tiles { left_over / 2 } else { 0 };
+    Some((first as i32, size as u16))
+  }
 }
+/** Pixels of a polygon in an area, relative to the top left of the area. */
+pub(crate) struct TilePixels {
+  /** Rows (y, x_start, x_end) of fill pixels that are fully covered, ends included. */
+  pub runs: Vec<(i32, i32, i32)>,
+  /** Pixels (x, y, coverage, fill) along the outline, see [`edge_coverage`]. */
+  pub edge: Vec<(i32, i32, f32, f32)>,
+}
+
+/**
+Pixels covered by the polygon made of `contours` in an area of `size` (width, height), relative to its top left. Pixels up to `reach` around the area are included for borders and smoothed edges that stick out.
+
+Only pixels within `near_reach` of the outline measure their distance to it, `coverage` then decides how much of them is covered like [`edge_coverage`]. Without `near_reach` every pixel is either fill or not covered.
+*/
+pub(crate) fn outline_pixels(
+  contours: &[Vec<(f32, f32)>],
+  size: (u16, u16),
+  fill_rule: FillRule,
+  reach: i32,
+  near_reach: Option<f32>,
+  coverage: impl Fn(bool, &dyn Fn(StrokeJoin) -> f32) -> Option<(f32, f32)>,
+) -> TilePixels {
+  let spans = poly_spans(contours, size.0, size.1, fill_rule);
+  let center = |x: i32, y: i32| (x as f32 + 0.5, y as f32 + 0.5);
+  let (width, height) = (size.0 as i32, size.1 as i32);
+  let mut inside = vec![false; width as usize * height as usize];
+  for span in &spans {
+    let row = span.y as usize * width as usize;
+    inside[row + span.x_start as usize..row + span.x_end as usize + 1].fill(true);
+  }
+
+  let (area_width, area_height) = (width + reach * 2, height + reach * 2);
+  let near = match near_reach {
+    Some(near_reach) => {
+      let shifted: Vec<Vec<(f32, f32)>> = contours
+        .iter()
+        .map(|contour| {
+          contour
+            .iter()
+            .map(|p| (p.0 + reach as f32, p.1 + reach as f32))
+            .collect()
+        })
+        .collect();
+      near_segments(
+        shifted
+          .iter()
+          .flat_map(|contour| contour_segments(contour, true)),
+        area_width as u16,
+        area_height as u16,
+        near_reach,
+      )
+    }
+    None => vec![false; area_width as usize * area_height as usize],
+  };
+
+  let mut pixels = TilePixels {
+    runs: Vec::new(),
+    edge: Vec::new(),
+  };
+  for y in -reach..height + reach {
+    let mut run_start = None;
+    for x in -reach..width + reach + 1 {
+      let is_near = x < width + reach && near[((y + reach) * area_width + x + reach) as usize];
+      let is_inside =
+        (0..width).contains(&x) && (0..height).contains(&y) && inside[(y * width + x) as usize];
+      //Pixels away from the outline are either fill or not covered at all.
+      if is_inside && !is_near {
+        run_start.get_or_insert(x);
+        continue;
+      }
+      if let Some(start) = run_start.take() {
+        pixels.runs.push((y, start, x - 1));
+      }
+      if !is_near {
+        continue;
+      }
+      let distance = |join| {
+        contours
+          .iter()
+          .map(|contour| distance_to_outline(contour, &center(x, y), join))
+          .fold(f32::MAX, f32::min)
+      };
+      if let Some((coverage, fill)) = coverage(is_inside, &distance) {
+        pixels.edge.push((x, y, coverage, fill));
+      }
+    }
+  }
+  pixels
+}
+
+/**
+How much of a pixel is covered by a polygon and its border and how much of that is fill rather than border, both in range [0, 1]. None if the pixel is not covered at all.
+
+`inside` is whether the pixel center is inside of the polygon, `distance` measures from the pixel center to the outline with the given join. `thickness` is the border (inside, outside) of the outline and `join` the shape of its corners. Without anti-aliasing coverage is always 1 and fill either 0 or 1.
+*/
+pub(crate) fn edge_coverage(
+  inside: bool,
+  distance: impl Fn(StrokeJoin) -> f32,
+  thickness: (f32, f32),
+  join: StrokeJoin,
+  anti_alias: bool,
+) -> Option<(f32, f32)> {
+  let (inside_thickness, outside_thickness) = thickness;
+  if !anti_alias {
+    return if inside {
+      let is_border = inside_thickness > 0.0 && distance(join) < inside_thickness;
+      Some((1.0, if is_border { 0.0 } else { 1.0 }))
+    } else if outside_thickness > 0.0 && distance(join) < outside_thickness {
+      Some((1.0, 0.0))
+    } else {
+      None
+    };
+  }
+  let signed = |distance: f32| if inside { -distance } else { distance };
+  //Without an outside border the edge is the polygon itself, joins only shape borders.
+  let outer = if outside_thickness > 0.0 {
+    signed(distance(join)) - outside_thickness
+  } else {
+    signed(distance(StrokeJoin::Round))
+  };
+  let coverage = (0.5 - outer).clamp(0.0, 1.0);
+  if coverage <= 0.0 {
+    return None;
+  }
+  if inside_thickness + outside_thickness <= 0.0 {
+    return Some((coverage, 1.0));
+  }
+  let inner = signed(distance(join)) + inside_thickness;
+  let fill = (0.5 - inner).clamp(0.0, coverage);
+  Some((coverage, fill / coverage))
+}
+
 impl Default for SpriteorPolyOp {
   fn default() -> Self {
     SpriteorPolyOp {
+      point_a: None,
+      point_b: None,
       x_count: 1,
       y_count: 1,
+      tile_gap: (0, 0),
+      padding: 0,
+      center_tiles: false,
+      stagger: TileStagger::None,
       polygon: DIAMOND_POLY.to_vec(),
+      contours: Vec::new(),
+      fill_rule: FillRule::EvenOdd,
+      transform: PolyTransform::default(),
+      alternate_transform: None,
+      alternate_tiles: TileAlternation::Checkerboard,
+      fit: PolyFit::Stretch,
+      align: (0.5, 0.5),
       border_thickness: 0,
+      stroke_align: StrokeAlign::Inside,
+      stroke_join: StrokeJoin::Mitre,
       border_color: None,
       fill_color: None,
+      anti_alias: true,
     }
   }
 }
 
 #[rustfmt::skip]
-pub(crate) const TRIANGLE_POLY: [(f32, f32); 3] = [
+pub const TRIANGLE_POLY: [(f32, f32); 3] = [
   (0.5, 0.0),
   (1.0, 1.0),
   (0.0, 1.0),
 ];
 #[rustfmt::skip]
-pub(crate) const SQUARE_POLY: [(f32, f32); 4] = [
+pub const SQUARE_POLY: [(f32, f32); 4] = [
   (0.0, 0.0),
   (1.0, 0.0),
   (1.0, 1.0),
   (0.0, 1.0),
 ];
 #[rustfmt::skip]
-pub(crate) const DIAMOND_POLY: [(f32, f32); 4] = [
+pub const DIAMOND_POLY: [(f32, f32); 4] = [
   (0.5, 0.0),
   (1.0, 0.5),
   (0.5, 1.0),
   (0.0, 0.5),
 ];
 #[rustfmt::skip]
-pub(crate) const PENTAGON_POLY: [(f32, f32); 5] = [
+pub const PENTAGON_POLY: [(f32, f32); 5] = [
   (0.5, 0.0),
   (1.0, 0.5),
   (0.66, 1.0),
@@ -137,7 +561,7 @@ pub(crate) const PENTAGON_POLY: [(f32, f32); 5] = [
   (0.0, 0.5),
 ];
 #[rustfmt::skip]
-pub(crate) const HEXAGON_POLY: [(f32, f32); 6] = [
+pub const HEXAGON_POLY: [(f32, f32); 6] = [
   (0.33, 0.0),
   (0.66, 0.0),
   (1.0, 0.5),
@@ -146,7 +570,7 @@ pub(crate) const HEXAGON_POLY: [(f32, f32); 6] = [
   (0.0, 0.5),
 ];
 #[rustfmt::skip]
-pub(crate) const OCTAGON_POLY: [(f32, f32); 8] = [
+pub const OCTAGON_POLY: [(f32, f32); 8] = [
   (0.33, 0.0),
   (0.66, 0.0),
   (1.0, 0.33),
@@ -157,7 +581,7 @@ pub(crate) const OCTAGON_POLY: [(f32, f32); 8] = [
   (0.0, 0.33),
 ];
 #[rustfmt::skip]
-pub(crate) const FOURSTAR_POLY: [(f32, f32); 8] = [
+pub const FOURSTAR_POLY: [(f32, f32); 8] = [
   (0.50, 0.00),
   (0.65, 0.35),
   (1.00, 0.50),
@@ -168,7 +592,7 @@ pub(crate) const FOURSTAR_POLY: [(f32, f32); 8] = [
   (0.35, 0.35),
 ];
 #[rustfmt::skip]
-pub(crate) const FIVESTAR_POLY: [(f32, f32); 10] = [
+pub const FIVESTAR_POLY: [(f32, f32); 10] = [
   (0.50, 0.00),
   (0.60, 0.25),
   (1.00, 0.375),
@@ -181,7 +605,7 @@ pub(crate) const FIVESTAR_POLY: [(f32, f32); 10] = [
   (0.40, 0.25),
 ];
 #[rustfmt::skip]
-pub(crate) const CROSS_POLY: [(f32, f32); 16] = [
+pub const CROSS_POLY: [(f32, f32); 16] = [
   (0.00, 0.00),
 
   (0.10, 0.00),
@@ -206,3 +630,234 @@ pub(crate) const CROSS_POLY: [(f32, f32); 16] = [
   (0.40, 0.50),
   (0.00, 0.10),
 ];
+
+/**
+Regular polygon with `sides` sides and a corner at the top, at least 3.
+
+Like the other shapes it is scaled to fill the unit square in its larger direction and centered in the other, so it stays regular in square tiles or with [`PolyFit::Contain`].
+*/
+pub fn regular_polygon(sides: u16) -> Vec<(f32, f32)> {
+  let sides = sides.max(3);
+  fit_unit_square(
+    (0..sides)
+      .map(|i| on_circle(i as f32 / sides as f32, 1.0))
+      .collect(),
+  )
+}
+
+/**
+Star with `points` points and the first one at the top, at least 2.
+
+The inner corners are at `inner_ratio` times the distance of the points from the center, 0.38 gives the classic five pointed star. Scaled like [`regular_polygon`].
+*/
+pub fn star_polygon(points: u16, inner_ratio: f32) -> Vec<(f32, f32)> {
+  let corners = points.max(2) as u32 * 2;
+  fit_unit_square(
+    (0..corners)
+      .map(|i| {
+        on_circle(
+          i as f32 / corners as f32,
+          if i % 2 == 0 { 1.0 } else { inner_ratio },
+        )
+      })
+      .collect(),
+  )
+}
+
+/**
+`polygon` with every corner replaced by a circular arc of `radius`, in the same units as the points.
+
+The radius is made smaller at corners where the arc would take more than half of one of the edges. Arcs have a point every 10 degrees, which is smooth enough for sprite sizes.
+*/
+pub fn rounded_polygon(polygon: &[(f32, f32)], radius: f32) -> Vec<(f32, f32)> {
+  let len = polygon.len();
+  let mut rounded = Vec::with_capacity(len * 4);
+  for i in 0..len {
+    let (previous, vertex, next) = (
+      polygon[(i + len - 1) % len],
+      polygon[i],
+      polygon[(i + 1) % len],
+    );
+    let to_previous = (previous.0 - vertex.0, previous.1 - vertex.1);
+    let to_next = (next.0 - vertex.0, next.1 - vertex.1);
+    let (previous_length, next_length) = (
+      to_previous.0.hypot(to_previous.1),
+      to_next.0.hypot(to_next.1),
+    );
+    //Angle between the edges, straight and folded back corners have nothing to round.
+    let angle = ((to_previous.0 * to_next.0 + to_previous.1 * to_next.1)
+      / (previous_length * next_length))
+      .clamp(-1.0, 1.0)
+      .acos();
+    if radius <= 0.0 || !angle.is_finite() || angle <= f32::EPSILON || angle >= PI - 0.001 {
+      rounded.push(vertex);
+      continue;
+    }
+    //The arc touches the edges `tangent` away from the corner.
+    let half_tan = (angle / 2.0).tan();
+    let tangent = (radius / half_tan).min(previous_length.min(next_length) / 2.0);
+    let radius = tangent * half_tan;
+    let start = (
+      vertex.0 + to_previous.0 / previous_length * tangent,
+      vertex.1 + to_previous.1 / previous_length * tangent,
+    );
+    let end = (
+      vertex.0 + to_next.0 / next_length * tangent,
+      vertex.1 + to_next.1 / next_length * tangent,
+    );
+    let bisector = (
+      to_previous.0 / previous_length + to_next.0 / next_length,
+      to_previous.1 / previous_length + to_next.1 / next_length,
+    );
+    let to_center = radius / (angle / 2.0).sin() / bisector.0.hypot(bisector.1);
+    let center = (
+      vertex.0 + bisector.0 * to_center,
+      vertex.1 + bisector.1 * to_center,
+    );
+
+    let start_angle = (start.1 - center.1).atan2(start.0 - center.0);
+    let end_angle = (end.1 - center.1).atan2(end.0 - center.0);
+    let sweep = (end_angle - start_angle + PI).rem_euclid(2.0 * PI) - PI;
+    let steps = (sweep.abs().to_degrees() / 10.0).ceil().max(1.0) as usize;
+    for step in 0..=steps {
+      let angle = start_angle + sweep * step as f32 / steps as f32;
+      rounded.push((
+        center.0 + radius * angle.cos(),
+        center.1 + radius * angle.sin(),
+      ));
+    }
+  }
+  rounded
+}
+
+/** Point at `turn` of a full clockwise turn from the top, `radius` away from the origin. */
+pub(crate) fn on_circle(turn: f32, radius: f32) -> (f32, f32) {
+  let (sin, cos) = (turn * 2.0 * PI).sin_cos();
+  (sin * radius, -cos * radius)
+}
+
+/** Scales and moves the points so they fill the unit square in the larger direction and are centered in the other. */
+fn fit_unit_square(points: Vec<(f32, f32)>) -> Vec<(f32, f32)> {
+  let (mut min, mut max) = ((f32::MAX, f32::MAX), (f32::MIN, f32::MIN));
+  for p in &points {
+    min = (min.0.min(p.0), min.1.min(p.1));
+    max = (max.0.max(p.0), max.1.max(p.1));
+  }
+  let size = (max.0 - min.0).max(max.1 - min.1);
+  let offset = (
+    (1.0 - (max.0 - min.0) / size) / 2.0,
+    (1.0 - (max.1 - min.1) / size) / 2.0,
+  );
+  points
+    .iter()
+    .map(|p| {
+      (
+        (p.0 - min.0) / size + offset.0,
+        (p.1 - min.1) / size + offset.1,
+      )
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use std::f32::consts::FRAC_1_SQRT_2;
+
+  use crate::debug::assert_near;
+
+  use super::*;
+
+  #[test]
+  fn regular_polygons_are_regular() {
+    let hexagon = regular_polygon(6);
+    assert_eq!(hexagon.len(), 6);
+    assert_near(hexagon[0], (0.5, 0.0));
+    assert_near(hexagon[3], (0.5, 1.0));
+    let side = |a: (f32, f32), b: (f32, f32)| (a.0 - b.0).hypot(a.1 - b.1);
+    for i in 1..6 {
+      assert!((side(hexagon[i - 1], hexagon[i]) - 0.5).abs() < 1e-5);
+    }
+    //A pentagon is wider than tall, so it touches the sides and is centered vertically.
+    let pentagon = regular_polygon(5);
+    assert_near(pentagon[1], (1.0, pentagon[1].1));
+    assert!(pentagon[0].1 > 0.0 && (pentagon[0].1 - (1.0 - pentagon[2].1)).abs() < 1e-5);
+    assert_eq!(regular_polygon(1).len(), 3);
+  }
+  #[test]
+  fn tile_layout_with_huge_gaps() {
+    let op = SpriteorPolyOp {
+      padding: 1,
+      center_tiles: true,
+      ..Default::default()
+    };
+    assert_eq!(op.tile_layout(0, 15, 3, 2), Some((1, 3)));
+    assert_eq!(op.tile_layout(0, 15, u16::MAX, u16::MAX), None);
+    //A single tile has no gaps, however wide they are.
+    assert_eq!(op.tile_layout(0, 15, 1, u16::MAX), Some((1, 14)));
+  }
+  #[test]
+  fn tile_interior_is_runs() {
+    let op = SpriteorPolyOp {
+      polygon: SQUARE_POLY.to_vec(),
+      ..Default::default()
+    };
+    let pixels = op.tile_pixels(&PolyTransform::default(), 64, 64);
+    //Only the ring of pixels along the edges needs the distance to the outline.
+    assert_eq!(pixels.edge.len(), 63 * 4);
+    assert!(pixels.runs.contains(&(32, 1, 62)));
+    let covered: i32 = pixels
+      .runs
+      .iter()
+      .map(|(_, start, end)| end - start + 1)
+      .sum();
+    assert_eq!(covered, 62 * 62);
+  }
+  #[test]
+  fn star_alternates_radius() {
+    let star = star_polygon(4, 0.5);
+    assert_eq!(star.len(), 8);
+    assert_near(star[0], (0.5, 0.0));
+    assert_near(star[2], (1.0, 0.5));
+    assert_near(
+      star[1],
+      (0.5 + 0.25 * FRAC_1_SQRT_2, 0.5 - 0.25 * FRAC_1_SQRT_2),
+    );
+  }
+  #[test]
+  fn rounded_corners_touch_the_edges() {
+    let rounded = rounded_polygon(&SQUARE_POLY, 0.25);
+    //A quarter circle per corner with a point every 10 degrees.
+    assert_eq!(rounded.len(), 4 * 10);
+    assert_near(rounded[0], (0.0, 0.25));
+    assert_near(rounded[9], (0.25, 0.0));
+    assert_near(rounded[10], (0.75, 0.0));
+    assert!(rounded[..10]
+      .iter()
+      .all(|p| ((p.0 - 0.25).hypot(p.1 - 0.25) - 0.25).abs() < 1e-5));
+    //Too large radii are limited to half of the edges.
+    assert_near(rounded_polygon(&SQUARE_POLY, 4.0)[0], (0.0, 0.5));
+    assert_eq!(rounded_polygon(&SQUARE_POLY, 0.0), SQUARE_POLY.to_vec());
+  }
+  #[test]
+  fn transform_order() {
+    assert_near(PolyTransform::default().apply(&(0.2, 0.9)), (0.2, 0.9));
+    let quarter = PolyTransform {
+      rotation: 90.0,
+      ..Default::default()
+    };
+    //Clockwise with y pointing down, the top goes to the right.
+    assert_near(quarter.apply(&(0.5, 0.0)), (1.0, 0.5));
+    //Flipped before rotating, so the left goes to the bottom instead of the top.
+    let flipped = PolyTransform {
+      flip_x: true,
+      ..quarter
+    };
+    assert_near(flipped.apply(&(0.0, 0.5)), (0.5, 1.0));
+    let moved = PolyTransform {
+      scale: (0.5, 2.0),
+      translate: (0.25, -0.5),
+      ..Default::default()
+    };
+    assert_near(moved.apply(&(1.0, 1.0)), (1.0, 1.0));
+  }
+}
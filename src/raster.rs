@@ -0,0 +1,518 @@
+/** How overlapping or self intersecting parts of a polygon are filled. */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FillRule {
+  /** A point is inside if a ray from it crosses the outline an odd number of times. */
+  #[default]
+  EvenOdd,
+  /** A point is inside if the outline winds around it at least once in either direction. */
+  NonZero,
+}
+
+/** Tolerance for pixel centers that are on an edge but miss it due to rounding. */
+const EDGE_EPSILON: f32 = 0.0001;
+
+/** Horizontal run of inside pixels on row `y`, from `x_start` up to and including `x_end`. */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Span {
+  pub y: u16,
+  pub x_start: u16,
+  pub x_end: u16,
+}
+
+#[derive(Debug)]
+struct Edge {
+  y_min: f32,
+  y_max: f32,
+  /** x at `y_min`. */
+  x: f32,
+  /** Change of x per unit of y. */
+  slope: f32,
+  /** +1 for edges going down, -1 for edges going up. */
+  winding: i32,
+}
+
+fn build_edges<C: AsRef<[(f32, f32)]>>(contours: &[C]) -> Vec<Edge> {
+  let mut edges = Vec::new();
+  for contour in contours {
+    let contour = contour.as_ref();
+    for i in 0..contour.len() {
+      let a = contour[i];
+      let b = contour[(i + 1) % contour.len()];
+      if a.1 == b.1 {
+        //Horizontal edges never cross a scanline.
+        continue;
+      }
+      let (top, bottom, winding) = if a.1 < b.1 { (a, b, 1) } else { (b, a, -1) };
+      edges.push(Edge {
+        y_min: top.1,
+        y_max: bottom.1,
+        x: top.0,
+        slope: (bottom.0 - top.0) / (bottom.1 - top.1),
+        winding,
+      });
+    }
+  }
+  edges.sort_by(|a, b| a.y_min.total_cmp(&b.y_min));
+  edges
+}
+
+/**
+Rasterizes a polygon with an active edge table and returns the spans of pixels whose center is inside it.
+
+The polygon is made of one or more closed `contours`, which are filled together with the fill rule so contours inside of others can make holes.
+
+Pixel (x, y) covers the area from (x, y) to (x + 1, y + 1), so its center is sampled at (x + 0.5, y + 0.5). Only pixels inside `width` x `height` are returned.
+
+Centers exactly on the outline are inside if the polygon continues to their right and below them, so on left edges that do not lean right going down, and on corners that stick out. This is what the per-pixel ray casting did for polygons with their points on pixels.
+*/
+pub(crate) fn poly_spans<C: AsRef<[(f32, f32)]>>(
+  contours: &[C],
+  width: u16,
+  height: u16,
+  fill_rule: FillRule,
+) -> Vec<Span> {
+  let edges = build_edges(contours);
+  let step = |winding: i32| match fill_rule {
+    FillRule::EvenOdd => 1,
+    FillRule::NonZero => winding,
+  };
+  let is_inside = |winding: i32| match fill_rule {
+    FillRule::EvenOdd => winding % 2 != 0,
+    FillRule::NonZero => winding != 0,
+  };
+  //Corners exactly on a pixel center that stick out of the polygon are inside, so thin tips are not lost.
+  let on_center = |value: f32| ((value - 0.5).round() - (value - 0.5)).abs() < EDGE_EPSILON;
+  let mut corners: Vec<(i32, i32)> = Vec::new();
+  for contour in contours {
+    let contour = contour.as_ref();
+    for (i, &p) in contour.iter().enumerate() {
+      let pixel = ((p.0 - 0.5).round() as i32, (p.1 - 0.5).round() as i32);
+      if !on_center(p.0)
+        || !on_center(p.1)
+        || !(0..width as i32).contains(&pixel.0)
+        || !(0..height as i32).contains(&pixel.1)
+      {
+        continue;
+      }
+      let previous = contour[(i + contour.len() - 1) % contour.len()];
+      let next = contour[(i + 1) % contour.len()];
+      let (Some(to_previous), Some(to_next)) = (unit(p, previous), unit(p, next)) else {
+        continue;
+      };
+      //A point just inside the narrower angle between the edges, it is in the polygon only for corners sticking out.
+      let Some(inward) = unit(
+        (0.0, 0.0),
+        (to_previous.0 + to_next.0, to_previous.1 + to_next.1),
+      ) else {
+        continue;
+      };
+      let probe = (p.0 + inward.0 * 0.01, p.1 + inward.1 * 0.01);
+      let winding: i32 = edges
+        .iter()
+        .filter(|e| e.y_min <= probe.1 && probe.1 < e.y_max)
+        .filter(|e| e.x + (probe.1 - e.y_min) * e.slope < probe.0)
+        .map(|e| step(e.winding))
+        .sum();
+      if is_inside(winding) {
+        corners.push(pixel);
+      }
+    }
+  }
+  let mut spans: Vec<Span> = Vec::new();
+  let mut next_edge = 0;
+  let mut active: Vec<&Edge> = Vec::new();
+  let mut crossings: Vec<(f32, i32, f32)> = Vec::new();
+  let mut row: Vec<(u16, u16)> = Vec::new();
+
+  for y in 0..height {
+    let sample_y = y as f32 + 0.5;
+
+    //Update the active edge table, edges are half open [y_min, y_max) so shared vertices only count once.
+    while next_edge < edges.len() && edges[next_edge].y_min <= sample_y {
+      active.push(&edges[next_edge]);
+      next_edge += 1;
+    }
+    active.retain(|e| e.y_max > sample_y);
+    row.clear();
+    row.extend(
+      corners
+        .iter()
+        .filter(|p| p.1 == y as i32)
+        .map(|p| (p.0 as u16, p.0 as u16)),
+    );
+
+    crossings.clear();
+    crossings.extend(
+      active
+        .iter()
+        .map(|e| (e.x + (sample_y - e.y_min) * e.slope, e.winding, e.slope)),
+    );
+    crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut winding = 0;
+    let mut previous_x = None;
+    let mut i = 0;
+    while i < crossings.len() {
+      //Crossings at the same x are handled together, they come from corners and touching edges.
+      let x = crossings[i].0;
+      let (mut passed, mut leaning_left) = (0, 0);
+      while i < crossings.len() && crossings[i].0 - x <= EDGE_EPSILON {
+        passed += step(crossings[i].1);
+        if crossings[i].2 <= 0.0 {
+          leaning_left += step(crossings[i].1);
+        }
+        i += 1;
+      }
+      if let Some(from) = previous_x.filter(|_| is_inside(winding)) {
+        row.extend(centers_between(from, x, width));
+      }
+      //A center on the outline needs the area right of it filled, and also below it where edges leaning right go.
+      if on_center(x) && is_inside(winding + passed) && is_inside(winding + leaning_left) {
+        let center = (x - 0.5).round();
+        if (0.0..width as f32).contains(&center) {
+          row.push((center as u16, center as u16));
+        }
+      }
+      winding += passed;
+      previous_x = Some(x);
+    }
+
+    row.sort_unstable();
+    for &(x_start, x_end) in &row {
+      match spans.last_mut() {
+        Some(last) if last.y == y && last.x_end + 1 >= x_start => {
+          last.x_end = last.x_end.max(x_end);
+        }
+        _ => spans.push(Span { y, x_start, x_end }),
+      }
+    }
+    if active.is_empty() && next_edge >= edges.len() && corners.iter().all(|p| p.1 <= y as i32) {
+      break;
+    }
+  }
+  spans
+}
+
+/** Direction from `from` to `to` with a length of 1, None if the points are the same. */
+fn unit(from: (f32, f32), to: (f32, f32)) -> Option<(f32, f32)> {
+  let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+  let length = (dx * dx + dy * dy).sqrt();
+  (length > EDGE_EPSILON).then(|| (dx / length, dy / length))
+}
+
+/** First and last pixel with its center strictly between `x_from` and `x_to`, None if there are none on a row `width` pixels wide. */
+fn centers_between(x_from: f32, x_to: f32, width: u16) -> Option<(u16, u16)> {
+  let x_start = ((x_from - 0.5 + EDGE_EPSILON).floor() + 1.0).max(0.0);
+  let x_end = ((x_to - 0.5 - EDGE_EPSILON).ceil() - 1.0).min(width as f32 - 1.0);
+  if x_end < x_start {
+    return None;
+  }
+  Some((x_start as u16, x_end as u16))
+}
+
+/** Indices of items sorted into square cells of an area by the box each can reach, so a pixel only measures the few items near it. */
+pub(crate) struct CellGrid {
+  cell: i32,
+  columns: i32,
+  rows: i32,
+  cells: Vec<Vec<usize>>,
+}
+impl CellGrid {
+  /** Empty grid over a `width` x `height` area with cells of `cell` pixels. */
+  pub fn new(width: u16, height: u16, cell: u16) -> CellGrid {
+    let cell = cell.max(1) as i32;
+    let (columns, rows) = (
+      (width as i32 + cell - 1) / cell,
+      (height as i32 + cell - 1) / cell,
+    );
+    CellGrid {
+      cell,
+      columns,
+      rows,
+      cells: vec![Vec::new(); (columns * rows) as usize],
+    }
+  }
+  /** Adds `item` to the cells touched by the box from `min` to `max`, parts outside of the area are left out. */
+  pub fn insert(&mut self, item: usize, min: (f32, f32), max: (f32, f32)) {
+    let cell = self.cell as f32;
+    let range = |from: f32, to: f32, count: i32| {
+      let first = (from / cell).floor().max(0.0) as i32;
+      let last = ((to / cell).floor() as i32).min(count - 1);
+      first..=last
+    };
+    for row in range(min.1, max.1, self.rows) {
+      for column in range(min.0, max.0, self.columns) {
+        self.cells[(row * self.columns + column) as usize].push(item);
+      }
+    }
+  }
+  /** Cells as (left, top, items), skipping the empty ones. Cells are `cell` pixels wide and high, less at the right and bottom of the area. */
+  pub fn occupied(&self) -> impl Iterator<Item = (i32, i32, &[usize])> + '_ {
+    self
+      .cells
+      .iter()
+      .enumerate()
+      .filter(|(_, items)| !items.is_empty())
+      .map(|(i, items)| {
+        let i = i as i32;
+        (
+          i % self.columns * self.cell,
+          i / self.columns * self.cell,
+          &items[..],
+        )
+      })
+  }
+  pub fn cell_size(&self) -> i32 {
+    self.cell
+  }
+}
+
+/** Segments between the points of a contour, with the one back to the start if it is `closed`. A single point is a segment of zero length. */
+pub(crate) fn contour_segments(
+  points: &[(f32, f32)],
+  closed: bool,
+) -> impl Iterator<Item = ((f32, f32), (f32, f32))> + '_ {
+  let count = if closed || points.len() == 1 {
+    points.len()
+  } else {
+    points.len().saturating_sub(1)
+  };
+  (0..count).map(move |i| (points[i], points[(i + 1) % points.len()]))
+}
+
+/**
+Marks the pixels of a `width` x `height` area whose center is at most `reach` pixels from one of the segments along both axes, row major.
+
+Only these pixels can be partly covered by an outline or a line along it, so they are the only ones that need the exact distance. Segments are cut to the area first, far away points cost nothing.
+*/
+pub(crate) fn near_segments(
+  segments: impl IntoIterator<Item = ((f32, f32), (f32, f32))>,
+  width: u16,
+  height: u16,
+  reach: f32,
+) -> Vec<bool> {
+  let (w, h) = (width as usize, height as usize);
+  let mut near = vec![false; w * h];
+  let margin = reach + 1.0;
+  let bounds = (
+    -margin,
+    -margin,
+    width as f32 + margin,
+    height as f32 + margin,
+  );
+  for (a, b) in segments {
+    let Some((a, b)) = clip_segment(a, b, bounds) else {
+      continue;
+    };
+    let row_start = (a.1.min(b.1) - reach - 0.5).ceil().max(0.0) as usize;
+    let row_end = (a.1.max(b.1) + reach - 0.5).floor().min(h as f32 - 1.0);
+    if row_end < row_start as f32 {
+      continue;
+    }
+    for y in row_start..=row_end as usize {
+      //Part of the segment within `reach` of the row.
+      let (x_from, x_to) = if a.1 == b.1 {
+        (a.0.min(b.0), a.0.max(b.0))
+      } else {
+        let x_at = |row_y: f32| {
+          let t = ((row_y - a.1) / (b.1 - a.1)).clamp(0.0, 1.0);
+          a.0 + (b.0 - a.0) * t
+        };
+        let center = y as f32 + 0.5;
+        let (x0, x1) = (x_at(center - reach), x_at(center + reach));
+        (x0.min(x1), x0.max(x1))
+      };
+      let x_start = (x_from - reach - 0.5).ceil().max(0.0) as usize;
+      let x_end = (x_to + reach - 0.5).floor().min(w as f32 - 1.0);
+      if x_end >= x_start as f32 {
+        near[y * w + x_start..=y * w + x_end as usize].fill(true);
+      }
+    }
+  }
+  near
+}
+
+/** Part of the segment from a to b inside of `bounds` (left, top, right, bottom), None if it misses them or is not finite. */
+fn clip_segment(
+  a: (f32, f32),
+  b: (f32, f32),
+  bounds: (f32, f32, f32, f32),
+) -> Option<((f32, f32), (f32, f32))> {
+  let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+  if !(a.0.is_finite() && a.1.is_finite() && dx.is_finite() && dy.is_finite()) {
+    return None;
+  }
+  //Cut points are put exactly on the bound, huge coordinates lose the precision to get there by interpolating.
+  let (mut from, mut to) = ((0.0_f32, a), (1.0_f32, b));
+  for (p, q, bound, vertical) in [
+    (-dx, a.0 - bounds.0, bounds.0, true),
+    (dx, bounds.2 - a.0, bounds.2, true),
+    (-dy, a.1 - bounds.1, bounds.1, false),
+    (dy, bounds.3 - a.1, bounds.3, false),
+  ] {
+    if p == 0.0 {
+      if q < 0.0 {
+        return None;
+      }
+      continue;
+    }
+    let t = q / p;
+    let point = if vertical {
+      (bound, a.1 + dy * t)
+    } else {
+      (a.0 + dx * t, bound)
+    };
+    if p < 0.0 && t > from.0 {
+      from = (t, point);
+    } else if p > 0.0 && t < to.0 {
+      to = (t, point);
+    }
+  }
+  if from.0 > to.0 {
+    return None;
+  }
+  Some((from.1, to.1))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn spans_to_rows(spans: &[Span], width: u16, height: u16) -> Vec<Vec<u8>> {
+    let mut rows = vec![vec![0; width as usize]; height as usize];
+    for span in spans {
+      for x in span.x_start..span.x_end + 1 {
+        rows[span.y as usize][x as usize] = 1;
+      }
+    }
+    rows
+  }
+
+  #[rustfmt::skip]
+  #[test]
+  fn square_covers_all_pixels() {
+    let square = vec![(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0)];
+    let spans = poly_spans(&[&square], 4, 4, FillRule::EvenOdd);
+    assert_eq!(spans, vec![
+      Span { y: 0, x_start: 0, x_end: 3 },
+      Span { y: 1, x_start: 0, x_end: 3 },
+      Span { y: 2, x_start: 0, x_end: 3 },
+      Span { y: 3, x_start: 0, x_end: 3 },
+    ]);
+  }
+  #[test]
+  fn spans_are_clipped_to_size() {
+    let square = vec![(-2.0, -2.0), (10.0, -2.0), (10.0, 10.0), (-2.0, 10.0)];
+    let rows = spans_to_rows(&poly_spans(&[&square], 4, 3, FillRule::EvenOdd), 4, 3);
+    assert_eq!(rows, vec![vec![1; 4]; 3]);
+  }
+  #[test]
+  fn empty_and_degenerate_polygons() {
+    assert_eq!(
+      poly_spans::<&[(f32, f32)]>(&[], 4, 4, FillRule::EvenOdd),
+      vec![]
+    );
+    assert_eq!(poly_spans(&[[]], 4, 4, FillRule::EvenOdd), vec![]);
+    let line = vec![(0.0, 0.0), (4.0, 4.0)];
+    assert_eq!(poly_spans(&[&line], 4, 4, FillRule::NonZero), vec![]);
+  }
+
+  #[rustfmt::skip]
+  #[test]
+  fn triangle_vertex_on_pixel_center() {
+    //The apex sits exactly on the center of pixel (2, 0), corners on a center are inside.
+    let triangle = vec![(2.5, 0.5), (5.0, 5.0), (0.0, 5.0)];
+    let rows = spans_to_rows(&poly_spans(&[&triangle], 5, 5, FillRule::EvenOdd), 5, 5);
+    assert_eq!(rows, vec![
+      vec![0, 0, 1, 0, 0],
+      vec![0, 0, 1, 0, 0],
+      vec![0, 1, 1, 1, 0],
+      vec![0, 1, 1, 1, 0],
+      vec![1, 1, 1, 1, 1],
+    ]);
+  }
+
+  #[rustfmt::skip]
+  #[test]
+  fn overlapping_squares_fill_rules() {
+    //Outer square clockwise and inner square clockwise, the inner one is a hole with even-odd only.
+    let polygon = vec![
+      (0.0, 0.0), (6.0, 0.0), (6.0, 6.0), (0.0, 6.0), (0.0, 0.0),
+      (2.0, 2.0), (4.0, 2.0), (4.0, 4.0), (2.0, 4.0), (2.0, 2.0),
+    ];
+    let even_odd = spans_to_rows(&poly_spans(&[&polygon], 6, 6, FillRule::EvenOdd), 6, 6);
+    assert_eq!(even_odd, vec![
+      vec![1, 1, 1, 1, 1, 1],
+      vec![1, 1, 1, 1, 1, 1],
+      vec![1, 1, 0, 0, 1, 1],
+      vec![1, 1, 0, 0, 1, 1],
+      vec![1, 1, 1, 1, 1, 1],
+      vec![1, 1, 1, 1, 1, 1],
+    ]);
+    let non_zero = spans_to_rows(&poly_spans(&[&polygon], 6, 6, FillRule::NonZero), 6, 6);
+    assert_eq!(non_zero, vec![vec![1; 6]; 6]);
+  }
+
+  #[rustfmt::skip]
+  #[test]
+  fn separate_contours_fill_rules() {
+    let outer = [(0.0, 0.0), (6.0, 0.0), (6.0, 6.0), (0.0, 6.0)];
+    let inner = [(2.0, 2.0), (4.0, 2.0), (4.0, 4.0), (2.0, 4.0)];
+    let mut reversed = inner;
+    reversed.reverse();
+    let hole = vec![
+      vec![1, 1, 1, 1, 1, 1],
+      vec![1, 1, 1, 1, 1, 1],
+      vec![1, 1, 0, 0, 1, 1],
+      vec![1, 1, 0, 0, 1, 1],
+      vec![1, 1, 1, 1, 1, 1],
+      vec![1, 1, 1, 1, 1, 1],
+    ];
+    let rows = |contours: &[[(f32, f32); 4]], fill_rule: FillRule| {
+      spans_to_rows(&poly_spans(contours, 6, 6, fill_rule), 6, 6)
+    };
+    assert_eq!(rows(&[outer, inner], FillRule::EvenOdd), hole);
+    assert_eq!(rows(&[outer, reversed], FillRule::EvenOdd), hole);
+    //With non-zero only a contour winding the other way cuts a hole.
+    assert_eq!(rows(&[outer, inner], FillRule::NonZero), vec![vec![1; 6]; 6]);
+    assert_eq!(rows(&[outer, reversed], FillRule::NonZero), hole);
+  }
+
+  #[rustfmt::skip]
+  #[test]
+  fn self_intersecting_star_fill_rules() {
+    //Pentagram drawn in one stroke, the center pentagon is wound twice.
+    let star: Vec<(f32, f32)> = (0..5)
+      .map(|i| {
+        let angle = (i as f32 * 144.0 - 90.0).to_radians();
+        (10.0 + 10.0 * angle.cos(), 10.0 + 10.0 * angle.sin())
+      })
+      .collect();
+    let even_odd = spans_to_rows(&poly_spans(&[&star], 20, 20, FillRule::EvenOdd), 20, 20);
+    let non_zero = spans_to_rows(&poly_spans(&[&star], 20, 20, FillRule::NonZero), 20, 20);
+    assert_eq!(even_odd[10][10], 0);
+    assert_eq!(non_zero[10][10], 1);
+    assert_eq!(even_odd[2][10], 1);
+    assert_eq!(non_zero[2][10], 1);
+  }
+
+  #[rustfmt::skip]
+  #[test]
+  fn near_segments_marks_pixels_along_the_outline() {
+    let square = [(1.0, 1.0), (5.0, 1.0), (5.0, 5.0), (1.0, 5.0)];
+    let near = near_segments(contour_segments(&square, true), 6, 6, 0.5);
+    let rows: Vec<Vec<u8>> = near.chunks(6).map(|row| row.iter().map(|n| *n as u8).collect()).collect();
+    assert_eq!(rows, vec![
+      vec![1, 1, 1, 1, 1, 1],
+      vec![1, 1, 1, 1, 1, 1],
+      vec![1, 1, 0, 0, 1, 1],
+      vec![1, 1, 0, 0, 1, 1],
+      vec![1, 1, 1, 1, 1, 1],
+      vec![1, 1, 1, 1, 1, 1],
+    ]);
+    //Far away points are cut off instead of walked.
+    let huge = [(-1e30, 3.0), (1e30, 3.0)];
+    let near = near_segments(contour_segments(&huge, false), 6, 6, 0.5);
+    assert_eq!(near.iter().filter(|n| **n).count(), 12);
+  }
+}
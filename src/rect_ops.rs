@@ -1,8 +1,25 @@
 use crate::{
-  colors::add_color_set_pixel,
+  border_style::{BorderStyle, StrokeAlign},
+  colors::{add_color, add_color_set_pixel, mix_color, scale_alpha},
+  effect_ops::{Bevel, InnerShadow},
+  error::{check_coordinate, check_range, SpriteorError, LENGTH_RANGE},
   maths::{distance_u16, xy_to_i, ContainsResult},
+  paint::Paint,
 };
 
+/** Shape of a corner of a [`SpriteorRectOp`], the border follows the shape. */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CornerStyle {
+  #[default]
+  Round,
+  /** Cut off in a straight line. */
+  Chamfer,
+  /** Square cut out of the corner. */
+  Notch,
+  /** Concave quarter circle or ellipse cut out of the corner. */
+  Scoop,
+}
+
 #[derive(Debug, Clone)]
 pub struct SpriteorRectOp {
   /**
@@ -18,9 +35,38 @@ pub struct SpriteorRectOp {
   */
   pub point_b: Option<(i16, i16)>,
   pub corner_radius: u16,
+  /**
+  Per corner override of `corner_radius` as (horizontal, vertical) radius, clockwise from top left.
+
+  Different radii give elliptical corners like CSS `border-radius: 10px / 4px`. Like CSS all radii are scaled down together when the corners on a side would overlap.
+  */
+  pub corner_radii: [Option<(u16, u16)>; 4],
+  /** Shape of each corner, clockwise from top left. The radii are the size of the corner for every style. */
+  pub corner_styles: [CornerStyle; 4],
+  /** Width of the border in pixels, must be in range [0, 4096]. */
   pub border_width: u16,
-  pub fill_color: Option<[u8; 4]>,
-  pub border_color: Option<[u8; 4]>,
+  /** Paint inside of the border, gradients span the whole rect. Defaults to light gray. */
+  pub fill_color: Option<Paint>,
+  /** Paint of the border, gradients span the whole rect. Defaults to white. */
+  pub border_color: Option<Paint>,
+  /** Per side override of `border_width` in CSS order: top, right, bottom, left. Each must be in range [0, 4096]. */
+  pub border_widths: [Option<u16>; 4],
+  /** Per side override of `border_color` in CSS order: top, right, bottom, left. Where two sides meet they are split diagonally. */
+  pub border_colors: [Option<Paint>; 4],
+  /** Dashes, dots and lighting of the border. Defaults to solid. */
+  pub border_style: BorderStyle,
+  /**
+  Where the border is drawn relative to the edges between `point_a` and `point_b`. Defaults to inside.
+
+  Center and outside borders grow the rect and its corner radii, the area inside of the border stays where it would be without a border. The part outside of the edges is not clipped by the parent rect, only by the sprite.
+  */
+  pub stroke_align: StrokeAlign,
+  /** Smooths rounded corners with partially transparent edge pixels. Disable for pixel art styles. Defaults to true. */
+  pub anti_alias: bool,
+  /** Shadow inside of the border, drawn over the fill. */
+  pub inner_shadow: Option<InnerShadow>,
+  /** Highlight and shadow along the edges, drawn over both border and fill. */
+  pub bevel: Option<Bevel>,
   pub debug: bool,
 }
 impl Default for SpriteorRectOp {
@@ -29,24 +75,63 @@ impl Default for SpriteorRectOp {
       point_a: None,
       point_b: None,
       corner_radius: 0,
+      corner_radii: [None; 4],
+      corner_styles: [CornerStyle::Round; 4],
       border_width: 0,
       fill_color: None,
       border_color: None,
+      border_widths: [None; 4],
+      border_colors: [None, None, None, None],
+      border_style: BorderStyle::Solid,
+      stroke_align: StrokeAlign::Inside,
+      anti_alias: true,
+      inner_shadow: None,
+      bevel: None,
       debug: false,
     }
   }
 }
 
+impl SpriteorRectOp {
+  pub(crate) fn validate(&self) -> Result<(), SpriteorError> {
+    for point in [self.point_a, self.point_b].iter().flatten() {
+      check_coordinate(point.0 as i32, point.1 as i32)?;
+    }
+    check_range("border_width", self.border_width as i32, LENGTH_RANGE)?;
+    for width in self.border_widths.iter().flatten() {
+      check_range("border_widths", *width as i32, LENGTH_RANGE)?;
+    }
+    self.border_style.validate()?;
+    if let Some(inner_shadow) = &self.inner_shadow {
+      inner_shadow.validate()?;
+    }
+    if let Some(bevel) = &self.bevel {
+      check_range("depth", bevel.depth as i32, LENGTH_RANGE)?;
+    }
+    Ok(())
+  }
+}
+
 #[derive(Debug)]
 pub(crate) struct RectOpUnw {
   pub top: u16,
   pub right: u16,
   pub bottom: u16,
   pub left: u16,
-  pub border_color: [u8; 4],
-  pub fill_color: [u8; 4],
-  radius: u16,
-  border_width: u16,
+  /** Top, right, bottom, left. */
+  pub border_colors: [Paint; 4],
+  pub fill_color: Paint,
+  /** Horizontal and vertical radius of each corner, clockwise from top left. */
+  radii: [(u16, u16); 4],
+  styles: [CornerStyle; 4],
+  /** Top, right, bottom, left. */
+  border_widths: [u16; 4],
+  border_style: BorderStyle,
+  /** Pixels the border extends past the edges the rect was placed at. Top, right, bottom, left. */
+  outsets: [u16; 4],
+  anti_alias: bool,
+  inner_shadow: Option<InnerShadow>,
+  bevel: Option<Bevel>,
   corners: [(u16, u16); 4],
   pub border_box_left: u16,
   pub border_box_right: u16,
@@ -55,38 +140,29 @@ pub(crate) struct RectOpUnw {
 }
 
 impl RectOpUnw {
-  pub fn new(top: u16, right: u16, bottom: u16, left: u16, fill: [u8; 4]) -> RectOpUnw {
-    RectOpUnw {
-      top,
-      right,
-      bottom,
-      left,
-      radius: 0,
-      border_width: 0,
-      fill_color: fill,
-      border_color: [0, 0, 0, 0],
-      corners: [(0, 0), (0, 0), (0, 0), (0, 0)],
-      border_box_left: 0,
-      border_box_right: 0,
-      border_box_top: 0,
-      border_box_bottom: 0,
-    }
-  }
   pub fn empty(sprite_width: &u16, sprite_height: &u16, margin: &u16) -> RectOpUnw {
+    let right = sprite_width - margin - 1;
+    let bottom = sprite_height - margin - 1;
     RectOpUnw {
       top: *margin,
-      right: sprite_width - margin - 1,
-      bottom: sprite_height - margin - 1,
+      right,
+      bottom,
       left: *margin,
-      radius: 0,
-      border_width: 0,
-      fill_color: [0, 0, 0, 0],
-      border_color: [0, 0, 0, 0],
+      radii: [(0, 0); 4],
+      styles: [CornerStyle::Round; 4],
+      border_widths: [0; 4],
+      border_style: BorderStyle::Solid,
+      outsets: [0; 4],
+      anti_alias: false,
+      inner_shadow: None,
+      bevel: None,
+      fill_color: Paint::Solid([0, 0, 0, 0]),
+      border_colors: transparent_sides(),
       corners: [(0, 0), (0, 0), (0, 0), (0, 0)],
-      border_box_left: 0,
-      border_box_right: 0,
-      border_box_top: 0,
-      border_box_bottom: 0,
+      border_box_left: *margin,
+      border_box_right: right,
+      border_box_top: *margin,
+      border_box_bottom: bottom,
     }
   }
   pub fn from_rect_op(
@@ -95,99 +171,511 @@ impl RectOpUnw {
     parent_right: u16,
     parent_bottom: u16,
     parent_left: u16,
-  ) -> RectOpUnw {
-    let parent_width = (parent_right - parent_left) as i16;
-    let parent_height = (parent_bottom - parent_top) as i16;
-    let a = op
-      .point_a
-      .unwrap_or((parent_left as i16, parent_top as i16));
-    let b = op.point_b.unwrap_or(if op.point_a.is_none() {
-      (parent_right as i16, parent_bottom as i16)
-    } else {
-      (parent_left as i16, parent_top as i16)
-    });
-
-    let a_positive = (
-      if a.0 < 0 {
-        (parent_width + a.0).max(0)
-      } else {
-        a.0
-      } as u16,
-      if a.1 < 0 {
-        (parent_height + a.1).max(0)
-      } else {
-        a.1
-      } as u16,
-    );
-    let b_positive = (
-      if b.0 < 0 {
-        (parent_width + b.0).max(0)
-      } else {
-        b.0
-      } as u16,
-      if b.1 < 0 {
-        (parent_height + b.1).max(0)
-      } else {
-        b.1
-      } as u16,
+  ) -> Result<RectOpUnw, SpriteorError> {
+    let (mut top, mut right, mut bottom, mut left) = resolve_area(
+      op.point_a,
+      op.point_b,
+      (parent_top, parent_right, parent_bottom, parent_left),
     );
 
-    let top = a_positive.1.min(b_positive.1).max(parent_top);
-    let right = a_positive.0.max(b_positive.0).min(parent_right);
-    let bottom = a_positive.1.max(b_positive.1).min(parent_bottom);
-    let left = a_positive.0.min(b_positive.0).max(parent_left);
+    let w = right - left;
+    let h = bottom - top;
 
-    let w = (right - left).max(0) as u16;
-    let h = (bottom - top).max(0) as u16;
+    let mut widths = op
+      .border_widths
+      .map(|width| width.unwrap_or(op.border_width));
+    let mut outsets = widths.map(|width| op.stroke_align.split(width).1);
+    let inset = |side: usize| (widths[side] - outsets[side]) as u32;
+    //Borders of opposite sides may meet or overlap, that just leaves an empty box inside.
+    let (pixels_x, pixels_y) = (w as u32 + 1, h as u32 + 1);
+    if inset(LEFT).max(inset(RIGHT)) > pixels_x || inset(TOP).max(inset(BOTTOM)) > pixels_y {
+      return Err(SpriteorError::BorderLargerThanBox {
+        border_width: widths.into_iter().max().unwrap_or(0),
+        width: w + 1,
+        height: h + 1,
+      });
+    }
 
-    let r = op.corner_radius.clone().min(w).min(h);
+    let radii = scale_radii(
+      op.corner_radii
+        .map(|radius| radius.unwrap_or((op.corner_radius, op.corner_radius))),
+      w + 1,
+      h + 1,
+    );
 
-    RectOpUnw {
+    //Borders past the top left of the sprite are cut off by making them narrower.
+    for (side, edge) in [(TOP, top), (LEFT, left)] {
+      let cut = outsets[side].saturating_sub(edge);
+      outsets[side] -= cut;
+      widths[side] -= cut;
+    }
+    //Validated widths keep this well inside of u16, saturating just stops unchecked ops from wrapping around.
+    let grow = |value: u16, outset: u16| (value as i32 + outset as i32).min(u16::MAX as i32) as u16;
+    top -= outsets[TOP];
+    right = grow(right, outsets[RIGHT]);
+    bottom = grow(bottom, outsets[BOTTOM]);
+    left -= outsets[LEFT];
+    //Rounded corners grow with the border so it keeps the same width around them.
+    let radii: [(u16, u16); 4] = std::array::from_fn(|corner| {
+      let (x_side, y_side) = corner_sides(corner);
+      match radii[corner] {
+        (0, 0) => (0, 0),
+        (rx, ry) => (grow(rx, outsets[x_side]), grow(ry, outsets[y_side])),
+      }
+    });
+    let border_color = op
+      .border_color
+      .clone()
+      .unwrap_or(Paint::Solid([255, 255, 255, 255]));
+    let (border_box_left, border_box_right) = inner_span(left, right, widths[LEFT], widths[RIGHT]);
+    let (border_box_top, border_box_bottom) = inner_span(top, bottom, widths[TOP], widths[BOTTOM]);
+
+    Ok(RectOpUnw {
       top,
       right,
       bottom,
       left,
-      radius: r,
-      border_width: op.border_width,
-      fill_color: op.fill_color.unwrap_or([200, 200, 200, 255]),
-      border_color: op.border_color.unwrap_or([255, 255, 255, 255]),
+      radii,
+      styles: op.corner_styles,
+      border_widths: widths,
+      border_style: op.border_style,
+      outsets,
+      anti_alias: op.anti_alias,
+      inner_shadow: op.inner_shadow.clone(),
+      bevel: op.bevel.clone(),
+      fill_color: op
+        .fill_color
+        .clone()
+        .unwrap_or(Paint::Solid([200, 200, 200, 255])),
+      border_colors: op
+        .border_colors
+        .clone()
+        .map(|paint| paint.unwrap_or_else(|| border_color.clone())),
       corners: [
-        (left + r, top + r),
-        (right - r, top + r),
-        (right - r, bottom - r),
-        (left + r, bottom - r),
+        (left + radii[0].0, top + radii[0].1),
+        (right - radii[1].0, top + radii[1].1),
+        (right - radii[2].0, bottom - radii[2].1),
+        (left + radii[3].0, bottom - radii[3].1),
       ],
-      /** Left edge inside of border. */
-      border_box_left: left + op.border_width,
-      /** Right edge inside of border. */
-      border_box_right: right - op.border_width,
-      /** Top edge inside of border. */
-      border_box_top: top + op.border_width,
-      /** Bottom edge inside of border. */
-      border_box_bottom: bottom - op.border_width,
-    }
+      //Edges inside of border.
+      border_box_left,
+      border_box_right,
+      border_box_top,
+      border_box_bottom,
+    })
+  }
+  /** Whether the border leaves no pixels inside of it, ops nested in the rect draw nothing then. */
+  pub fn border_box_is_empty(&self) -> bool {
+    self.border_box_left > self.border_box_right || self.border_box_top > self.border_box_bottom
   }
 
-  pub fn add_to(&self, values: &mut Vec<u8>, container: &RectOpUnw, sprite_width: &u16) {
-    for y in self.top..self.bottom + 1 {
-      for x in self.left..self.right + 1 {
-        let contained = self.contains(&x, &y);
-        match contained {
-          ContainsResult::Border => {
-            container.add_to_pixel_if_inside(values, sprite_width, &x, &y, &self.border_color)
+  pub fn add_to(&self, values: &mut [u8], container: &RectOpUnw, sprite_width: &u16) {
+    let width = (self.right - self.left + 1) as usize;
+    let height = (self.bottom - self.top + 1) as usize;
+    let sprite_height = (values.len() / 4 / *sprite_width as usize) as u16;
+    let inner_shadow = self.inner_shadow.as_ref().map(|shadow| {
+      //The area inside of the border, its corners are rounded by what is left of the radii.
+      let (left, top) = (self.left as f32, self.top as f32);
+      let outer_radii = self.outer_radii();
+      let inner_radii: [(f32, f32); 4] = std::array::from_fn(|corner| {
+        let (x_side, y_side) = corner_sides(corner);
+        (
+          (outer_radii[corner].0 - self.border_widths[x_side] as f32).max(0.0),
+          (outer_radii[corner].1 - self.border_widths[y_side] as f32).max(0.0),
+        )
+      });
+      let map = shadow.alpha_map(width, height, |x, y| {
+        let distance = box_distance(
+          left + x,
+          top + y,
+          (
+            self.border_box_left,
+            self.border_box_top,
+            self.border_box_right,
+            self.border_box_bottom,
+          ),
+          &inner_radii,
+        );
+        (0.5 - distance).clamp(0.0, 1.0)
+      });
+      (shadow, map)
+    });
+    //Outside borders can reach past the sprite, only the part on it is drawn.
+    let (last_x, last_y) = (
+      self.right.min(sprite_width.saturating_sub(1)),
+      self.bottom.min(sprite_height.saturating_sub(1)),
+    );
+    for y in self.top..=last_y {
+      for x in self.left..=last_x {
+        let (coverage, fill) = self.coverage(&x, &y);
+        if coverage <= 0.0 {
+          continue;
+        }
+        let mut color = self.color_at(&x, &y, fill);
+        if let Some((shadow, map)) = &inner_shadow {
+          let i = (y - self.top) as usize * width + (x - self.left) as usize;
+          let alpha = map[i] * fill;
+          if alpha > 0.0 {
+            color = add_color(&color, &scale_alpha(&shadow.color, alpha));
           }
-          ContainsResult::Inside => {
-            container.add_to_pixel_if_inside(values, sprite_width, &x, &y, &self.fill_color)
+        }
+        if let Some(bevel) = &self.bevel {
+          let (distance, normal) = self.edge_distance(x as f32, y as f32);
+          if let Some(bevel_color) = bevel.color_at(distance, normal) {
+            color = add_color(&color, &bevel_color);
           }
-          _ => (),
+        }
+        let color = scale_alpha(&color, coverage);
+        if self.in_outset(&x, &y) {
+          add_color_set_pixel(values, &xy_to_i(sprite_width, &x, &y), &color);
+        } else {
+          container.add_to_pixel_if_inside(values, sprite_width, &x, &y, &color);
         }
       }
     }
   }
 
+  /** Whether the pixel is in the part of the border outside of the edges the rect was placed at. */
+  fn in_outset(&self, x: &u16, y: &u16) -> bool {
+    *y < self.top + self.outsets[TOP]
+      || *x > self.right - self.outsets[RIGHT]
+      || *y > self.bottom - self.outsets[BOTTOM]
+      || *x < self.left + self.outsets[LEFT]
+  }
+
+  /**
+  Signed distance from a point to the outer edge of the rect, negative inside.
+
+  Like for anti-aliasing the edges are half a pixel outside of the outermost pixel centers, and rounded corners get the extra half pixel of radius.
+  */
+  pub fn signed_distance(&self, x: f32, y: f32) -> f32 {
+    box_distance(
+      x,
+      y,
+      (self.left, self.top, self.right, self.bottom),
+      &self.outer_radii(),
+    )
+  }
+
+  /** Corner radii of the outer edge, including the extra half pixel that rounded corners get. */
+  fn outer_radii(&self) -> [(f32, f32); 4] {
+    self.radii.map(|(rx, ry)| {
+      if rx > 0 {
+        (rx as f32 + 0.5, ry as f32 + 0.5)
+      } else {
+        (0.0, 0.0)
+      }
+    })
+  }
+
+  /** Radii (x, y) of the inner curve of the border in a corner, clockwise from top left. Negative if the border is wider than the radius. */
+  fn inner_corner_radii(&self, corner: usize) -> (i32, i32) {
+    let (x_side, y_side) = corner_sides(corner);
+    (
+      self.radii[corner].0 as i32 - self.border_widths[x_side] as i32,
+      self.radii[corner].1 as i32 - self.border_widths[y_side] as i32,
+    )
+  }
+
+  /**
+  Side of the border the pixel belongs to, top, right, bottom or left.
+
+  Picks the side the pixel is relatively closest to, so where two sides meet the border is split on the line from the outer to the inner corner. Top and bottom win ties.
+  */
+  fn border_side(&self, x: &u16, y: &u16) -> usize {
+    self.border_side_depth(x, y).0
+  }
+
+  /** Like `border_side`, also returns how far the pixel center is into that side relative to its width. */
+  fn border_side_depth(&self, x: &u16, y: &u16) -> (usize, f32) {
+    let (x, y) = (*x as f32, *y as f32);
+    let distances = [
+      y - self.top as f32 + 0.5,
+      self.right as f32 + 0.5 - x,
+      self.bottom as f32 + 0.5 - y,
+      x - self.left as f32 + 0.5,
+    ];
+    let mut side = TOP;
+    let mut closest = f32::MAX;
+    for s in [TOP, BOTTOM, RIGHT, LEFT] {
+      if self.border_widths[s] == 0 {
+        continue;
+      }
+      let relative = distances[s] / self.border_widths[s] as f32;
+      if relative < closest {
+        closest = relative;
+        side = s;
+      }
+    }
+    (side, closest)
+  }
+
+  /**
+  How far the pixel center is into the border in range [0, 1] from the outer to the inner edge, and the width of the border there.
+
+  In corners the width is the distance between the outer and inner curve, so it changes gradually from one side to the other.
+  */
+  fn border_depth(&self, x: &u16, y: &u16) -> (f32, f32) {
+    let (depth, width) = if let Some(corner) = self.styled_corner_at(x, y) {
+      let (outer, inner) = self.styled_corner_distances(x, y, corner);
+      ((outer + 0.5) / (outer - inner), outer - inner)
+    } else if let Some(corner) = self.corner_at(x, y) {
+      let center = &self.corners[corner];
+      let d = distance_u16(*x, *y, center.0, center.1);
+      let (dx, dy) = (*x as f32 - center.0 as f32, *y as f32 - center.1 as f32);
+      let (rx, ry) = self.radii[corner];
+      let outer = ellipse_radius(rx as f32, ry as f32, dx, dy, d);
+      let (rx, ry) = self.inner_corner_radii(corner);
+      let inner = if rx <= 0 || ry <= 0 {
+        rx.min(ry) as f32
+      } else {
+        ellipse_radius(rx as f32, ry as f32, dx, dy, d)
+      };
+      ((outer - d + 0.5) / (outer - inner), outer - inner)
+    } else {
+      let (side, depth) = self.border_side_depth(x, y);
+      (depth, self.border_widths[side] as f32)
+    };
+    (depth.clamp(0.0, 1.0), width)
+  }
+
+  /**
+  Position of the pixel along the middle of the border and the length of the part of the border it is on.
+
+  Each side is a part of its own, from the middle of the corner before it to the middle of the corner after it clockwise, so patterns can be fitted to every side. Round corners are measured along their arc, everywhere else the pixel is projected onto the side it belongs to.
+  */
+  fn border_position(&self, x: &u16, y: &u16) -> (f32, f32) {
+    let widths = self.border_widths.map(|width| width as f32 / 2.0);
+    let middles = [
+      self.top as f32 - 0.5 + widths[TOP],
+      self.right as f32 + 0.5 - widths[RIGHT],
+      self.bottom as f32 + 0.5 - widths[BOTTOM],
+      self.left as f32 - 0.5 + widths[LEFT],
+    ];
+    let arcs: [f32; 4] = std::array::from_fn(|corner| {
+      let (rx, ry) = self.radii[corner];
+      if self.styles[corner] != CornerStyle::Round || rx == 0 {
+        return 0.0;
+      }
+      let (x_side, y_side) = corner_sides(corner);
+      let a = (rx as f32 + 0.5 - widths[x_side]).max(0.0);
+      let b = (ry as f32 + 0.5 - widths[y_side]).max(0.0);
+      //Ramanujan's approximation of the circumference of an ellipse, divided by 4.
+      std::f32::consts::PI * (3.0 * (a + b) - ((3.0 * a + b) * (a + 3.0 * b)).sqrt()) / 4.0
+    });
+    //Where the straight sides end, square corners end where the middles of both sides cross.
+    let c: [(f32, f32); 4] = std::array::from_fn(|corner| {
+      let (x_side, y_side) = corner_sides(corner);
+      if self.radii[corner] == (0, 0) {
+        (middles[x_side], middles[y_side])
+      } else {
+        (self.corners[corner].0 as f32, self.corners[corner].1 as f32)
+      }
+    });
+    let sides = [
+      c[1].0 - c[0].0,
+      c[2].1 - c[1].1,
+      c[2].0 - c[3].0,
+      c[3].1 - c[0].1,
+    ];
+    //Side k runs from corner k to corner k + 1.
+    let length = |side: usize| (arcs[side] + arcs[(side + 1) % 4]) / 2.0 + sides[side];
+
+    let (fx, fy) = (*x as f32, *y as f32);
+    let round_corner = self
+      .corner_at(x, y)
+      .filter(|corner| self.styles[*corner] == CornerStyle::Round);
+    if let Some(corner) = round_corner {
+      let (rx, ry) = self.radii[corner];
+      let dx = (fx - c[corner].0) / rx as f32;
+      let dy = (fy - c[corner].1) / ry as f32;
+      let angle = match corner {
+        0 => (-dy).atan2(-dx),
+        1 => dx.atan2(-dy),
+        2 => dy.atan2(dx),
+        _ => (-dx).atan2(dy),
+      };
+      let along = angle / std::f32::consts::FRAC_PI_2;
+      if along < 0.5 {
+        let side = (corner + 3) % 4;
+        return (
+          arcs[side] / 2.0 + sides[side] + along * arcs[corner],
+          length(side),
+        );
+      }
+      return ((along - 0.5) * arcs[corner], length(corner));
+    }
+    let side = self.border_side(x, y);
+    let along = match side {
+      TOP => fx - c[0].0,
+      RIGHT => fy - c[1].1,
+      BOTTOM => c[2].0 - fx,
+      _ => c[3].1 - fy,
+    };
+    (arcs[side] / 2.0 + along, length(side))
+  }
+
+  /** Distance from a point inside of the rect to its edge, and the direction straight out of that edge. */
+  fn edge_distance(&self, x: f32, y: f32) -> (f32, (f32, f32)) {
+    let distance = self.signed_distance(x, y);
+    let dx = self.signed_distance(x + 0.5, y) - self.signed_distance(x - 0.5, y);
+    let dy = self.signed_distance(x, y + 0.5) - self.signed_distance(x, y - 0.5);
+    let length = dx.hypot(dy);
+    let normal = if length > 0.0 {
+      (dx / length, dy / length)
+    } else {
+      (0.0, 0.0)
+    };
+    (-distance, normal)
+  }
+
+  /** Border and fill paint at the pixel, mixed by how much of it is fill. */
+  fn color_at(&self, x: &u16, y: &u16, fill: f32) -> [u8; 4] {
+    let paint_at =
+      |paint: &Paint| paint.color_at(x, y, &self.left, &self.top, &self.right, &self.bottom);
+    if fill >= 1.0 {
+      return paint_at(&self.fill_color);
+    }
+    let side = self.border_side(x, y);
+    let mut border_color = paint_at(&self.border_colors[side]);
+    if self.border_style != BorderStyle::Solid {
+      let (position, length) = self.border_position(x, y);
+      let (depth, width) = self.border_depth(x, y);
+      let lit = side == TOP || side == LEFT;
+      border_color = self
+        .border_style
+        .color_at(&border_color, position, length, depth, width, lit)
+        .unwrap_or_else(|| {
+          //Gaps show nothing, the fill color keeps anti-aliased edges from blending towards the border color.
+          let fill_color = paint_at(&self.fill_color);
+          [fill_color[0], fill_color[1], fill_color[2], 0]
+        });
+    }
+    if fill <= 0.0 {
+      return border_color;
+    }
+    mix_color(&border_color, &paint_at(&self.fill_color), fill)
+  }
+
+  /**
+  Returns how much of the pixel is covered by the rect and how much of that is fill rather than border, both in range [0, 1].
+
+  Without anti-aliasing this is the same as `contains` with only 0 or 1 as results.
+  */
+  pub fn coverage(&self, x: &u16, y: &u16) -> (f32, f32) {
+    if self.anti_alias {
+      if let Some(corner) = self.styled_corner_at(x, y) {
+        let (outer, inner) = self.styled_corner_distances(x, y, corner);
+        let outer = (outer + 1.0).clamp(0.0, 1.0);
+        if outer <= 0.0 {
+          return (0.0, 0.0);
+        }
+        return (outer, (inner + 1.0).clamp(0.0, outer) / outer);
+      }
+      if let Some(corner) = self.corner_at(x, y) {
+        return corner_coverage(x, y, corner, self);
+      }
+    }
+    match self.contains(x, y) {
+      ContainsResult::Inside => (1.0, 1.0),
+      ContainsResult::Border => (1.0, 0.0),
+      ContainsResult::Outside => (0.0, 0.0),
+    }
+  }
+
+  /** Index of the rounded corner area the pixel is in, clockwise from top left. */
+  fn corner_at(&self, x: &u16, y: &u16) -> Option<usize> {
+    if x < &self.left || x > &self.right || y < &self.top || y > &self.bottom {
+      return None;
+    }
+    let corner = if x < &self.corners[0].0 && y < &self.corners[0].1 {
+      0
+    } else if x > &self.corners[1].0 && y < &self.corners[1].1 {
+      1
+    } else if x > &self.corners[2].0 && y > &self.corners[2].1 {
+      2
+    } else if x < &self.corners[3].0 && y > &self.corners[3].1 {
+      3
+    } else {
+      return None;
+    };
+    //Square corners have no area of their own.
+    if self.radii[corner] == (0, 0) {
+      return None;
+    }
+    Some(corner)
+  }
+
+  /** Pixel position relative to the outer corner, counted inwards along the sides. */
+  fn corner_offset(&self, x: &u16, y: &u16, corner: usize) -> (f32, f32) {
+    let u = if corner == 0 || corner == 3 {
+      x - self.left
+    } else {
+      self.right - x
+    };
+    let v = if corner < 2 {
+      y - self.top
+    } else {
+      self.bottom - y
+    };
+    (u as f32, v as f32)
+  }
+
+  /**
+  Index of the non-round corner whose shape or border can reach the pixel, clockwise from top left.
+
+  Unlike round corners these reach past the radii, since their border runs along the inside of the cut.
+  */
+  fn styled_corner_at(&self, x: &u16, y: &u16) -> Option<usize> {
+    if x < &self.left || x > &self.right || y < &self.top || y > &self.bottom {
+      return None;
+    }
+    (0..4).find(|corner| {
+      if self.styles[*corner] == CornerStyle::Round || self.radii[*corner] == (0, 0) {
+        return false;
+      }
+      let (u, v) = self.corner_offset(x, y, *corner);
+      let (x_side, y_side) = corner_sides(*corner);
+      u < (self.radii[*corner].0 + self.border_widths[x_side] + 1) as f32
+        && v < (self.radii[*corner].1 + self.border_widths[y_side] + 1) as f32
+    })
+  }
+
+  /**
+  Signed distances (outer, inner) in pixels from the pixel center to the outer edge and to the inner edge of the border, positive inside.
+
+  Like the straight sides a pixel is fully inside at distance 0.
+  */
+  fn styled_corner_distances(&self, x: &u16, y: &u16, corner: usize) -> (f32, f32) {
+    let (u, v) = self.corner_offset(x, y, corner);
+    let (rx, ry) = (self.radii[corner].0 as f32, self.radii[corner].1 as f32);
+    let (x_side, y_side) = corner_sides(corner);
+    let (wx, wy) = (
+      self.border_widths[x_side] as f32,
+      self.border_widths[y_side] as f32,
+    );
+    let (outer, inner) = match self.styles[corner] {
+      CornerStyle::Chamfer => {
+        let line = (u / rx + v / ry - 1.0) / (1.0 / rx).hypot(1.0 / ry);
+        (line, line - (wx + wy) / 2.0)
+      }
+      CornerStyle::Notch => ((u - rx).max(v - ry), (u - rx - wx).max(v - ry - wy)),
+      CornerStyle::Scoop => {
+        let d = u.hypot(v);
+        (
+          d - ellipse_radius(rx, ry, u, v, d),
+          d - ellipse_radius(rx + wx, ry + wy, u, v, d),
+        )
+      }
+      CornerStyle::Round => unreachable!("round corners are not styled corners"),
+    };
+    //The straight sides still bound the fill.
+    (outer, inner.min(u - wx).min(v - wy))
+  }
+
   pub fn add_to_pixel_if_inside(
     &self,
-    values: &mut Vec<u8>,
+    values: &mut [u8],
     sprite_width: &u16,
     x: &u16,
     y: &u16,
@@ -202,21 +690,20 @@ impl RectOpUnw {
     if x < &self.left || x > &self.right || y < &self.top || y > &self.bottom {
       return ContainsResult::Outside;
     }
-    if self.radius > 0 {
-      if x < &self.corners[0].0 && y < &self.corners[0].1 {
-        return check_corner_pixel(x, y, &self.corners[0], &self);
+    if let Some(corner) = self.styled_corner_at(x, y) {
+      let (outer, inner) = self.styled_corner_distances(x, y, corner);
+      if outer < 0.0 {
+        return ContainsResult::Outside;
       }
-      if x > &self.corners[1].0 && y < &self.corners[1].1 {
-        return check_corner_pixel(x, y, &self.corners[1], &self);
-      }
-      if x > &self.corners[2].0 && y > &self.corners[2].1 {
-        return check_corner_pixel(x, y, &self.corners[2], &self);
-      }
-      if x < &self.corners[3].0 && y > &self.corners[3].1 {
-        return check_corner_pixel(x, y, &self.corners[3], &self);
+      if inner < 0.0 {
+        return ContainsResult::Border;
       }
+      return ContainsResult::Inside;
     }
-    if self.border_width > 0 {
+    if let Some(corner) = self.corner_at(x, y) {
+      return check_corner_pixel(x, y, corner, self);
+    }
+    if self.border_widths != [0; 4] {
       let border_x_valid = (x >= &self.left && x < &self.border_box_left)
         || (x <= &self.right && x > &self.border_box_right);
       let border_y_valid = (y >= &self.top && y < &self.border_box_top)
@@ -230,29 +717,214 @@ impl RectOpUnw {
   }
 }
 
-fn check_corner_pixel(x: &u16, y: &u16, corner: &(u16, u16), rect: &RectOpUnw) -> ContainsResult {
-  let d = distance_u16(*x, *y, corner.0, corner.1);
-  if d <= rect.radius as f32 {
-    //The pixel is inside box.
-    if d >= (rect.radius - rect.border_width) as f32 {
-      //This pixel is inside the border.
-      return ContainsResult::Border;
+/**
+Edges (top, right, bottom, left) of the area between `point_a` and `point_b` of an op inside of the `parent` edges in the same order.
+
+Without points the area is the whole parent, without `point_b` it reaches from the top left of the parent to `point_a`. Negative coordinates are counted from the right and bottom.
+*/
+pub(crate) fn resolve_area(
+  point_a: Option<(i16, i16)>,
+  point_b: Option<(i16, i16)>,
+  parent: (u16, u16, u16, u16),
+) -> (u16, u16, u16, u16) {
+  let (parent_top, parent_right, parent_bottom, parent_left) = parent;
+  let a = point_a.unwrap_or((parent_left as i16, parent_top as i16));
+  let b = point_b.unwrap_or(if point_a.is_none() {
+    (parent_right as i16, parent_bottom as i16)
+  } else {
+    (parent_left as i16, parent_top as i16)
+  });
+
+  let resolve_x = |x: i16| resolve_coordinate(x, parent_left, parent_right);
+  let resolve_y = |y: i16| resolve_coordinate(y, parent_top, parent_bottom);
+  let a = (resolve_x(a.0), resolve_y(a.1));
+  let b = (resolve_x(b.0), resolve_y(b.1));
+  (a.1.min(b.1), a.0.max(b.0), a.1.max(b.1), a.0.min(b.0))
+}
+
+/** Pixel of the coordinate `value` of an op along a parent from `first` to `last`, negative values are counted from the end. Clamped to the parent so edges can never end up inverted. */
+pub(crate) fn resolve_coordinate(value: i16, first: u16, last: u16) -> u16 {
+  let length = last.saturating_sub(first) as i16;
+  (if value < 0 {
+    (length + value).max(0)
+  } else {
+    value
+  } as u16)
+    .clamp(first, last)
+}
+
+/**
+First and last pixel between the borders at the start and end of a span. When the borders fill the whole span the first pixel is one past the last.
+*/
+fn inner_span(start: u16, end: u16, start_width: u16, end_width: u16) -> (u16, u16) {
+  let first = start as i32 + start_width as i32;
+  let last = end as i32 - end_width as i32;
+  if last >= first {
+    (first as u16, last as u16)
+  } else {
+    let first = first.clamp(1, u16::MAX as i32);
+    (first as u16, (first - 1) as u16)
+  }
+}
+
+const TOP: usize = 0;
+const RIGHT: usize = 1;
+const BOTTOM: usize = 2;
+const LEFT: usize = 3;
+
+fn transparent_sides() -> [Paint; 4] {
+  [(); 4].map(|_| Paint::Solid([0, 0, 0, 0]))
+}
+
+/** Sides (horizontal, vertical) that meet in a corner, clockwise from top left. */
+fn corner_sides(corner: usize) -> (usize, usize) {
+  (
+    if corner == 0 || corner == 3 {
+      LEFT
+    } else {
+      RIGHT
+    },
+    if corner < 2 { TOP } else { BOTTOM },
+  )
+}
+
+/**
+Scales all radii down by the same factor so the corners on each side fit in its length, like CSS does.
+
+A radius of 0 in either direction makes the corner square.
+*/
+fn scale_radii(radii: [(u16, u16); 4], width: u16, height: u16) -> [(u16, u16); 4] {
+  let sides = [
+    (radii[0].0 as u32 + radii[1].0 as u32, width),
+    (radii[3].0 as u32 + radii[2].0 as u32, width),
+    (radii[0].1 as u32 + radii[3].1 as u32, height),
+    (radii[1].1 as u32 + radii[2].1 as u32, height),
+  ];
+  let scale = sides
+    .iter()
+    .filter(|(sum, _)| *sum > 0)
+    .map(|(sum, length)| *length as f32 / *sum as f32)
+    .fold(1.0_f32, f32::min);
+  //A corner's center has to stay inside the box, so a lone radius can't reach past the far edge.
+  radii.map(|(rx, ry)| {
+    let scaled = (
+      ((rx as f32 * scale).floor() as u16).min(width - 1),
+      ((ry as f32 * scale).floor() as u16).min(height - 1),
+    );
+    if scaled.0 == 0 || scaled.1 == 0 {
+      (0, 0)
+    } else {
+      scaled
     }
+  })
+}
+
+/** Radius of an ellipse in the direction of (dx, dy), which is `d` long. */
+fn ellipse_radius(rx: f32, ry: f32, dx: f32, dy: f32, d: f32) -> f32 {
+  if rx == ry || d == 0.0 {
+    return rx.min(ry);
+  }
+  rx * ry * d / ((dx * ry).powi(2) + (dy * rx).powi(2)).sqrt()
+}
+
+/**
+Signed distance from a point to the edge of a box with rounded corners, negative inside.
+
+`bounds` are the outermost pixels (left, top, right, bottom), the edges are half a pixel outside of them. `radii` are clockwise from top left. Elliptical corners use a first order approximation, which is exact for circles.
+*/
+fn box_distance(x: f32, y: f32, bounds: (u16, u16, u16, u16), radii: &[(f32, f32); 4]) -> f32 {
+  let (left, top, right, bottom) = bounds;
+  let half_width = (right - left) as f32 / 2.0 + 0.5;
+  let half_height = (bottom - top) as f32 / 2.0 + 0.5;
+  let center_x = (left + right) as f32 / 2.0;
+  let center_y = (top + bottom) as f32 / 2.0;
+  let corner = match (x < center_x, y < center_y) {
+    (true, true) => 0,
+    (false, true) => 1,
+    (false, false) => 2,
+    (true, false) => 3,
+  };
+  let (rx, ry) = radii[corner];
+  let px = (x - center_x).abs() - half_width;
+  let py = (y - center_y).abs() - half_height;
+  let (qx, qy) = (px + rx, py + ry);
+  if qx <= 0.0 || qy <= 0.0 {
+    return px.max(py);
+  }
+  if rx == ry {
+    return qx.hypot(qy) - rx;
+  }
+  let k = (qx / rx).hypot(qy / ry);
+  let gradient = (qx / (rx * rx)).hypot(qy / (ry * ry)) / k;
+  (k - 1.0) / gradient
+}
+
+/**
+Outer and inner edge are ellipses around the same point. The inner radii are the outer ones minus the widths of the sides next to the corner, like CSS.
+*/
+fn check_corner_pixel(x: &u16, y: &u16, corner: usize, rect: &RectOpUnw) -> ContainsResult {
+  let center = &rect.corners[corner];
+  let dx = *x as i64 - center.0 as i64;
+  let dy = *y as i64 - center.1 as i64;
+  //Compared without dividing so circles give exactly the same pixels as comparing distances.
+  let ellipse = |rx: i64, ry: i64| (dx * dx * ry * ry + dy * dy * rx * rx, rx * rx * ry * ry);
+  let (outer_x, outer_y) = rect.radii[corner];
+  let (lhs, rhs) = ellipse(outer_x as i64, outer_y as i64);
+  if lhs > rhs {
+    return ContainsResult::Outside;
+  }
+  let (rx, ry) = rect.inner_corner_radii(corner);
+  if rx <= 0 || ry <= 0 {
+    //Border is at least as wide as the radius, so the inner corner is square and outside of the border box.
+    return ContainsResult::Border;
+  }
+  let (lhs, rhs) = ellipse(rx as i64, ry as i64);
+  if lhs < rhs {
     return ContainsResult::Inside;
   }
-  ContainsResult::Outside
+  ContainsResult::Border
+}
+
+/**
+Anti-aliased version of `check_corner_pixel`.
+
+Pixel edges of the straight sides are half a pixel outside of the outermost pixel centers, so the arcs get the same extra half pixel of radius to meet them. Coverage is then approximated from the distance between the pixel center and the arcs, using the radius of the ellipses in the direction of the pixel.
+*/
+fn corner_coverage(x: &u16, y: &u16, corner: usize, rect: &RectOpUnw) -> (f32, f32) {
+  let center = &rect.corners[corner];
+  let d = distance_u16(*x, *y, center.0, center.1);
+  let dx = *x as f32 - center.0 as f32;
+  let dy = *y as f32 - center.1 as f32;
+  let (outer_x, outer_y) = rect.radii[corner];
+  let outer_radius = ellipse_radius(outer_x as f32, outer_y as f32, dx, dy, d);
+  let outer = (outer_radius + 1.0 - d).clamp(0.0, 1.0);
+  if outer <= 0.0 {
+    return (0.0, 0.0);
+  }
+  let (rx, ry) = rect.inner_corner_radii(corner);
+  let (rx, ry) = (rx as f32, ry as f32);
+  let inner_radius = if rx <= 0.0 || ry <= 0.0 {
+    rx.min(ry)
+  } else {
+    ellipse_radius(rx, ry, dx, dy, d)
+  };
+  let inner = (inner_radius + 1.0 - d).clamp(0.0, outer);
+  (outer, inner / outer)
 }
 
 #[cfg(test)]
 mod tests {
-  use crate::debug::{pixels_to_values, print_matrix};
+  use crate::{
+    debug::{pixels_to_values, print_matrix, render_rows, white_or_color},
+    spriteor::{SpriteorOperation, SpriteorSettings},
+  };
 
   use super::*;
 
   #[test]
   fn add_rect_default() {
-    let size = 16 as u16;
-    let mut values = vec![0 as u8; size as usize * size as usize * 4];
+    let size = 16_u16;
+    let mut values = vec![0_u8; size as usize * size as usize * 4];
     let container = RectOpUnw::empty(&size, &size, &0);
     let rect = RectOpUnw::from_rect_op(
       &SpriteorRectOp {
@@ -265,14 +937,653 @@ mod tests {
       size,
       size,
       0,
-    );
+    )
+    .unwrap();
 
     rect.add_to(&mut values, &container, &size);
     print_matrix(&values, size, 2);
     let values = pixels_to_values(
-      &vec![1 as u8; size as usize * size as usize],
+      &vec![1_u8; size as usize * size as usize],
       &[200, 200, 200, 255],
     );
     assert_eq!(values, values);
   }
+
+  fn rounded_rect(anti_alias: bool, border_width: u16) -> RectOpUnw {
+    RectOpUnw::from_rect_op(
+      &SpriteorRectOp {
+        corner_radius: 4,
+        border_width,
+        fill_color: Some(Paint::Solid([0, 0, 255, 255])),
+        border_color: Some(Paint::Solid([255, 0, 0, 255])),
+        anti_alias,
+        ..Default::default()
+      },
+      0,
+      15,
+      15,
+      0,
+    )
+    .unwrap()
+  }
+
+  #[test]
+  fn anti_aliased_corner_has_partial_coverage() {
+    let rect = rounded_rect(true, 0);
+    assert_eq!(rect.coverage(&0, &0), (0.0, 0.0));
+    assert_eq!(rect.coverage(&0, &8), (1.0, 1.0));
+    assert_eq!(rect.coverage(&8, &8), (1.0, 1.0));
+    let (coverage, fill) = rect.coverage(&1, &1);
+    assert!(coverage > 0.0 && coverage < 1.0);
+    assert_eq!(fill, 1.0);
+    //Pixels inside the hard edge stay fully covered.
+    assert_eq!(rect.coverage(&1, &2).0, 1.0);
+  }
+  #[test]
+  fn anti_aliased_corners_are_symmetric() {
+    let rect = rounded_rect(true, 1);
+    for y in 0..4 {
+      for x in 0..4 {
+        let top_left = rect.coverage(&x, &y);
+        assert_eq!(top_left, rect.coverage(&(15 - x), &y));
+        assert_eq!(top_left, rect.coverage(&(15 - x), &(15 - y)));
+        assert_eq!(top_left, rect.coverage(&x, &(15 - y)));
+        assert_eq!(top_left, rect.coverage(&y, &x));
+      }
+    }
+  }
+  #[test]
+  fn anti_aliased_border_blends_into_fill() {
+    let size = 16_u16;
+    let mut values = vec![0_u8; size as usize * size as usize * 4];
+    let container = RectOpUnw::empty(&size, &size, &0);
+    rounded_rect(true, 2).add_to(&mut values, &container, &size);
+    print_matrix(&values, size, 3);
+    let partial_alpha = values.chunks(4).filter(|c| c[3] > 0 && c[3] < 255).count();
+    let blended = values
+      .chunks(4)
+      .filter(|c| c[0] > 0 && c[0] < 255 && c[2] > 0 && c[2] < 255)
+      .count();
+    assert!(partial_alpha >= 8);
+    assert!(blended >= 4);
+  }
+  #[test]
+  fn pixel_art_corner_is_hard() {
+    let size = 16_u16;
+    let mut values = vec![0_u8; size as usize * size as usize * 4];
+    let container = RectOpUnw::empty(&size, &size, &0);
+    rounded_rect(false, 2).add_to(&mut values, &container, &size);
+    print_matrix(&values, size, 3);
+    assert!(values
+      .chunks(4)
+      .all(|c| c == [0, 0, 0, 0] || c == [255, 0, 0, 255] || c == [0, 0, 255, 255]));
+  }
+  #[test]
+  fn signed_distance_follows_rounded_corners() {
+    let rect = rounded_rect(true, 0);
+    assert_eq!(rect.signed_distance(0.0, 8.0), -0.5);
+    assert_eq!(rect.signed_distance(-0.5, 8.0), 0.0);
+    assert_eq!(rect.signed_distance(7.5, 7.5), -8.0);
+    //Corner center is at (4, 4) with 4.5 pixels of radius.
+    let corner = 4.0 - 4.5 * std::f32::consts::FRAC_1_SQRT_2;
+    assert!(rect.signed_distance(corner, corner).abs() < 0.0001);
+  }
+  #[test]
+  fn bevel_and_inner_shadow_shade_the_rect() {
+    let size = 16_u16;
+    let mut values = vec![0_u8; size as usize * size as usize * 4];
+    let container = RectOpUnw::empty(&size, &size, &0);
+    let rect = RectOpUnw::from_rect_op(
+      &SpriteorRectOp {
+        corner_radius: 4,
+        fill_color: Some(Paint::Solid([100, 100, 100, 255])),
+        inner_shadow: Some(InnerShadow {
+          offset: (0, 2),
+          blur_radius: 0,
+          color: [0, 0, 0, 255],
+        }),
+        bevel: Some(Bevel {
+          angle: 270.0,
+          depth: 1,
+          ..Default::default()
+        }),
+        ..Default::default()
+      },
+      0,
+      15,
+      15,
+      0,
+    )
+    .unwrap();
+    rect.add_to(&mut values, &container, &size);
+    print_matrix(&values, size, 3);
+    let pixel = |x: usize, y: usize| &values[(y * 16 + x) * 4..(y * 16 + x) * 4 + 4];
+    //Inner shadow below the top edge, untouched in the middle.
+    assert!(pixel(8, 1)[0] < 100);
+    assert_eq!(pixel(8, 8), [100, 100, 100, 255]);
+    //Light from the left lights the left edge and darkens the right edge.
+    assert!(pixel(0, 8)[0] > 100);
+    assert!(pixel(15, 8)[0] < 100);
+  }
+  fn sided_op(corner_radius: u16, border_widths: [Option<u16>; 4]) -> SpriteorRectOp {
+    SpriteorRectOp {
+      corner_radius,
+      border_widths,
+      border_colors: [
+        Some(Paint::Solid([255, 0, 0, 255])),
+        Some(Paint::Solid([0, 255, 0, 255])),
+        Some(Paint::Solid([0, 0, 255, 255])),
+        Some(Paint::Solid([255, 255, 0, 255])),
+      ],
+      anti_alias: false,
+      ..Default::default()
+    }
+  }
+  /** Draws the rect on an empty 8 x 8 sprite, border pixels are the first letter of their side and fill pixels '.'. */
+  fn side_rows(op: SpriteorRectOp) -> Vec<String> {
+    let settings = SpriteorSettings {
+      width: 8,
+      height: 8,
+      ..Default::default()
+    };
+    render_rows(
+      &settings,
+      vec![SpriteorOperation::SpriteorRectOp(op)],
+      |pixel| match pixel {
+        [_, _, _, 0] => ' ',
+        [255, 0, 0, _] => 't',
+        [0, 255, 0, _] => 'r',
+        [0, 0, 255, _] => 'b',
+        [255, 255, 0, _] => 'l',
+        _ => '.',
+      },
+    )
+  }
+
+  #[rustfmt::skip]
+  #[test]
+  fn per_side_widths_with_mitred_joins() {
+    let op = sided_op(0, [Some(2), Some(1), Some(0), Some(2)]);
+    let rect = RectOpUnw::from_rect_op(&op, 0, 7, 7, 0).unwrap();
+    assert_eq!(
+      (rect.border_box_top, rect.border_box_right, rect.border_box_bottom, rect.border_box_left),
+      (2, 6, 7, 2)
+    );
+    assert_eq!(side_rows(op), vec![
+      "tttttttt",
+      "lttttttr",
+      "ll.....r",
+      "ll.....r",
+      "ll.....r",
+      "ll.....r",
+      "ll.....r",
+      "ll.....r",
+    ]);
+  }
+  #[rustfmt::skip]
+  #[test]
+  fn per_side_widths_with_rounded_corners() {
+    //Only a bottom border, it thins out along the rounded bottom corners and the top corners have none.
+    assert_eq!(side_rows(sided_op(3, [Some(0), Some(0), Some(2), Some(0)])), vec![
+      "   ..   ",
+      " ...... ",
+      " ...... ",
+      "........",
+      "........",
+      " bb..bb ",
+      " bbbbbb ",
+      "   bb   ",
+    ]);
+  }
+  #[test]
+  fn per_side_widths_must_fit() {
+    let sided = |widths: [Option<u16>; 4]| {
+      RectOpUnw::from_rect_op(
+        &SpriteorRectOp {
+          border_widths: widths,
+          ..Default::default()
+        },
+        0,
+        7,
+        7,
+        0,
+      )
+    };
+    //Borders that fill the whole width leave an empty box inside.
+    let rect = sided([None, Some(4), None, Some(4)]).unwrap();
+    assert!(rect.border_box_is_empty());
+    assert_eq!((rect.border_box_left, rect.border_box_right), (4, 3));
+    assert_eq!((rect.border_box_top, rect.border_box_bottom), (0, 7));
+    //Overlapping ones too, all pixels are border then.
+    let rect = sided([None, Some(6), None, Some(5)]).unwrap();
+    assert_eq!((rect.border_box_left, rect.border_box_right), (5, 4));
+    assert_eq!(
+      sided([None, Some(9), None, Some(4)]).err(),
+      Some(SpriteorError::BorderLargerThanBox {
+        border_width: 9,
+        width: 8,
+        height: 8
+      })
+    );
+  }
+  #[test]
+  fn single_pixel_filled_by_border() {
+    let rect = RectOpUnw::from_rect_op(
+      &SpriteorRectOp {
+        point_a: Some((0, 0)),
+        point_b: Some((0, 0)),
+        border_width: 1,
+        ..Default::default()
+      },
+      0,
+      7,
+      7,
+      0,
+    )
+    .unwrap();
+    assert!(rect.border_box_is_empty());
+    assert_eq!((rect.border_box_left, rect.border_box_right), (1, 0));
+  }
+  /** Draws the rect filling an empty `width` x `height` sprite, border pixels are '#' and fill pixels '.'. */
+  fn radii_rows(op: SpriteorRectOp, width: u16, height: u16) -> Vec<String> {
+    //Sprites are at least 8 pixels high, lower rects leave the rows below empty.
+    let settings = SpriteorSettings {
+      width,
+      height: height.max(8),
+      ..Default::default()
+    };
+    let op = SpriteorRectOp {
+      point_a: Some((0, 0)),
+      point_b: Some((width as i16 - 1, height as i16 - 1)),
+      anti_alias: false,
+      ..op
+    };
+    let mut rows = render_rows(
+      &settings,
+      vec![SpriteorOperation::SpriteorRectOp(op)],
+      white_or_color,
+    );
+    rows.truncate(height as usize);
+    rows
+  }
+
+  #[rustfmt::skip]
+  #[test]
+  fn only_top_corners_rounded() {
+    let rows = radii_rows(
+      SpriteorRectOp {
+        corner_radii: [Some((3, 3)), Some((3, 3)), Some((0, 0)), Some((0, 0))],
+        ..Default::default()
+      },
+      10,
+      6,
+    );
+    assert_eq!(rows, vec![
+      "   ....   ",
+      " ........ ",
+      " ........ ",
+      "..........",
+      "..........",
+      "..........",
+    ]);
+  }
+  #[rustfmt::skip]
+  #[test]
+  fn elliptical_corners_with_border() {
+    //The flat bottom corners have a thinner inner curve, so the bottom border is thicker towards the sides.
+    let rows = radii_rows(
+      SpriteorRectOp {
+        corner_radius: 6,
+        corner_radii: [None, None, Some((6, 2)), Some((6, 2))],
+        border_width: 1,
+        ..Default::default()
+      },
+      16,
+      10,
+    );
+    assert_eq!(rows, vec![
+      "      ####      ",
+      "   ###....###   ",
+      "  ##........##  ",
+      " ##..........## ",
+      " #............# ",
+      " #............# ",
+      "#..............#",
+      "#..............#",
+      " #####....##### ",
+      "      ####      ",
+    ]);
+  }
+  #[test]
+  fn overlapping_radii_are_scaled_together() {
+    assert_eq!(
+      scale_radii([(10, 10), (10, 10), (2, 2), (2, 2)], 10, 40),
+      [(5, 5), (5, 5), (1, 1), (1, 1)]
+    );
+    assert_eq!(
+      scale_radii([(4, 2), (4, 2), (4, 2), (4, 2)], 16, 8),
+      [(4, 2), (4, 2), (4, 2), (4, 2)]
+    );
+    //Scaled down to (2, 0), which is a square corner.
+    assert_eq!(
+      scale_radii([(4, 1), (4, 1), (0, 0), (0, 0)], 4, 8),
+      [(0, 0); 4]
+    );
+    //A lone corner as large as the box ends at the far edge.
+    assert_eq!(
+      scale_radii([(0, 0), (16, 16), (0, 0), (0, 0)], 16, 16),
+      [(0, 0), (15, 15), (0, 0), (0, 0)]
+    );
+    let rows = radii_rows(
+      SpriteorRectOp {
+        corner_radii: [None, Some((16, 16)), None, None],
+        ..Default::default()
+      },
+      16,
+      16,
+    );
+    assert_eq!(&rows[0], ".               ");
+    assert_eq!(&rows[15], "................");
+  }
+  #[test]
+  fn elliptical_distance_is_zero_on_the_edge() {
+    let radii = [(4.5, 2.5); 4];
+    //Top left corner of a box with edges at -0.5 and 15.5, ellipse center at (4, 2).
+    let angle = 1.0_f32;
+    let (x, y) = (4.0 - 4.5 * angle.cos(), 2.0 - 2.5 * angle.sin());
+    assert!(box_distance(x, y, (0, 0, 15, 15), &radii).abs() < 0.0001);
+    assert!(box_distance(x + 0.5, y + 0.5, (0, 0, 15, 15), &radii) < 0.0);
+    assert_eq!(box_distance(8.0, -0.5, (0, 0, 15, 15), &radii), 0.0);
+  }
+  fn styled_rows(style: CornerStyle) -> Vec<String> {
+    radii_rows(
+      SpriteorRectOp {
+        corner_radius: 4,
+        border_width: 1,
+        corner_styles: [style, style, CornerStyle::Round, style],
+        ..Default::default()
+      },
+      12,
+      10,
+    )
+  }
+
+  #[rustfmt::skip]
+  #[test]
+  fn chamfered_corners() {
+    assert_eq!(styled_rows(CornerStyle::Chamfer), vec![
+      "    ####    ",
+      "   ##..##   ",
+      "  ##....##  ",
+      " ##......## ",
+      "##........##",
+      "#..........#",
+      " ##.......# ",
+      "  ##......# ",
+      "   ##...##  ",
+      "    ####    ",
+    ]);
+  }
+  #[rustfmt::skip]
+  #[test]
+  fn notched_corners() {
+    assert_eq!(styled_rows(CornerStyle::Notch), vec![
+      "    ####    ",
+      "    #..#    ",
+      "    #..#    ",
+      "    #..#    ",
+      "#####..#####",
+      "#..........#",
+      "    #.....# ",
+      "    #.....# ",
+      "    #...##  ",
+      "    ####    ",
+    ]);
+  }
+  #[rustfmt::skip]
+  #[test]
+  fn scooped_corners() {
+    assert_eq!(styled_rows(CornerStyle::Scoop), vec![
+      "    ####    ",
+      "    #..#    ",
+      "    #..#    ",
+      "   #....#   ",
+      "###......###",
+      "#..........#",
+      "   #......# ",
+      "    #.....# ",
+      "    #...##  ",
+      "    ####    ",
+    ]);
+  }
+  #[test]
+  fn anti_aliased_chamfer_edge() {
+    let rect = RectOpUnw::from_rect_op(
+      &SpriteorRectOp {
+        corner_radius: 4,
+        corner_styles: [CornerStyle::Chamfer; 4],
+        ..Default::default()
+      },
+      0,
+      15,
+      15,
+      0,
+    )
+    .unwrap();
+    assert_eq!(rect.coverage(&0, &4), (1.0, 1.0));
+    assert_eq!(rect.coverage(&0, &0), (0.0, 0.0));
+    let (coverage, fill) = rect.coverage(&1, &2);
+    assert!(coverage > 0.0 && coverage < 1.0);
+    assert_eq!(fill, 1.0);
+  }
+  /** Draws the rect into the border box of an empty sprite with `margin`, fill pixels are '.' and border pixels '#'. */
+  fn drawn_rows(op: SpriteorRectOp, width: u16, height: u16, margin: u16) -> Vec<String> {
+    let settings = SpriteorSettings {
+      width,
+      height,
+      margin,
+      ..Default::default()
+    };
+    let op = SpriteorRectOp {
+      fill_color: Some(Paint::Solid([0, 0, 255, 255])),
+      anti_alias: false,
+      ..op
+    };
+    render_rows(
+      &settings,
+      vec![SpriteorOperation::SpriteorRectOp(op)],
+      |pixel| match pixel {
+        [_, _, _, 0] => ' ',
+        [0, 0, 255, _] => '.',
+        _ => '#',
+      },
+    )
+  }
+  fn border_style_rows(
+    border_style: BorderStyle,
+    corner_radius: u16,
+    border_width: u16,
+  ) -> Vec<String> {
+    drawn_rows(
+      SpriteorRectOp {
+        corner_radius,
+        border_width,
+        border_style,
+        ..Default::default()
+      },
+      12,
+      10,
+      0,
+    )
+  }
+
+  #[rustfmt::skip]
+  #[test]
+  fn dashes_are_centered_on_corners() {
+    assert_eq!(border_style_rows(BorderStyle::Dashed { dash: 2, gap: 2 }, 0, 1), vec![
+      "#  ##  ##  #",
+      "#..........#",
+      " .......... ",
+      " .......... ",
+      "#..........#",
+      "#..........#",
+      " .......... ",
+      " .......... ",
+      "#..........#",
+      "#  ##  ##  #",
+    ]);
+  }
+  #[rustfmt::skip]
+  #[test]
+  fn dashes_follow_rounded_corners() {
+    assert_eq!(border_style_rows(BorderStyle::Dashed { dash: 3, gap: 2 }, 4, 1), vec![
+      "     ##     ",
+      "  # .... #  ",
+      " #........# ",
+      " #........# ",
+      " .......... ",
+      " .......... ",
+      " #........# ",
+      " #........# ",
+      "  # .... #  ",
+      "     ##     ",
+    ]);
+  }
+  #[rustfmt::skip]
+  #[test]
+  fn dotted_border() {
+    assert_eq!(border_style_rows(BorderStyle::Dotted { gap: 1 }, 0, 2), vec![
+      "## ##  ## ##",
+      "## ##  ## ##",
+      "  ........  ",
+      "##........##",
+      "##........##",
+      "##........##",
+      "##........##",
+      "  ........  ",
+      "## ##  ## ##",
+      "## ##  ## ##",
+    ]);
+  }
+  #[rustfmt::skip]
+  #[test]
+  fn double_border() {
+    assert_eq!(border_style_rows(BorderStyle::Double, 0, 3), vec![
+      "############",
+      "#          #",
+      "# ######## #",
+      "# #......# #",
+      "# #......# #",
+      "# #......# #",
+      "# #......# #",
+      "# ######## #",
+      "#          #",
+      "############",
+    ]);
+  }
+  #[test]
+  fn inset_border_is_darker_on_top_and_left() {
+    let size = 8_u16;
+    let mut values = vec![0_u8; size as usize * size as usize * 4];
+    RectOpUnw::from_rect_op(
+      &SpriteorRectOp {
+        border_width: 2,
+        border_color: Some(Paint::Solid([128, 128, 128, 255])),
+        border_style: BorderStyle::Inset,
+        ..Default::default()
+      },
+      0,
+      size - 1,
+      size - 1,
+      0,
+    )
+    .unwrap()
+    .add_to(&mut values, &RectOpUnw::empty(&size, &size, &0), &size);
+    print_matrix(&values, size, 3);
+    let red = |x: usize, y: usize| values[(y * size as usize + x) * 4];
+    assert_eq!(red(3, 0), red(0, 3));
+    assert_eq!(red(3, 7), red(7, 3));
+    assert!(red(3, 0) < 128 && red(3, 7) > 128);
+    //Corners are split diagonally between the sides.
+    assert_eq!(red(0, 6), red(0, 3));
+    assert_eq!(red(1, 7), red(3, 7));
+  }
+  fn aligned_rows(stroke_align: StrokeAlign, corner_radius: u16) -> Vec<String> {
+    drawn_rows(
+      SpriteorRectOp {
+        corner_radius,
+        border_width: 2,
+        stroke_align,
+        ..Default::default()
+      },
+      12,
+      12,
+      2,
+    )
+  }
+
+  #[rustfmt::skip]
+  #[test]
+  fn stroke_alignment() {
+    assert_eq!(aligned_rows(StrokeAlign::Inside, 0)[1..6], [
+      "            ",
+      "  ########  ",
+      "  ########  ",
+      "  ##....##  ",
+      "  ##....##  ",
+    ]);
+    assert_eq!(aligned_rows(StrokeAlign::Center, 0)[..5], [
+      "            ",
+      " ########## ",
+      " ########## ",
+      " ##......## ",
+      " ##......## ",
+    ]);
+    assert_eq!(aligned_rows(StrokeAlign::Outside, 0)[..4], [
+      "############",
+      "############",
+      "##........##",
+      "##........##",
+    ]);
+  }
+  #[rustfmt::skip]
+  #[test]
+  fn outside_stroke_grows_corner_radius() {
+    //The fill keeps its radius of 2, the outer edge of the border gets 4.
+    assert_eq!(aligned_rows(StrokeAlign::Outside, 2), vec![
+      "    ####    ",
+      "  ########  ",
+      " ###....### ",
+      " ##......## ",
+      "##........##",
+      "##........##",
+      "##........##",
+      "##........##",
+      " ##......## ",
+      " ###....### ",
+      "  ########  ",
+      "    ####    ",
+    ]);
+  }
+  #[test]
+  fn outside_stroke_is_cut_at_sprite_edge() {
+    let rect = RectOpUnw::from_rect_op(
+      &SpriteorRectOp {
+        point_a: Some((1, 1)),
+        point_b: Some((6, 6)),
+        border_width: 3,
+        stroke_align: StrokeAlign::Outside,
+        ..Default::default()
+      },
+      0,
+      7,
+      7,
+      0,
+    )
+    .unwrap();
+    assert_eq!((rect.left, rect.top, rect.right, rect.bottom), (0, 0, 9, 9));
+    assert_eq!(rect.border_widths, [1, 3, 3, 1]);
+    assert_eq!((rect.border_box_left, rect.border_box_top), (1, 1));
+    assert_eq!((rect.border_box_right, rect.border_box_bottom), (6, 6));
+  }
 }
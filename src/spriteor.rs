@@ -1,24 +1,121 @@
+use std::{io, path::Path};
+
 use crate::{
+  colors::add_color_set_pixel,
   debug::print_matrix,
-  line_ops::{SpriteorHLineOp, SpriteorVLineOp},
+  effect_ops::{SpriteorGlowOp, SpriteorShadowOp},
+  ellipse_ops::SpriteorEllipseOp,
+  error::{SpriteorError, SIZE_RANGE},
+  line_ops::{SpriteorHLineOp, SpriteorLineOp, SpriteorVLineOp},
+  path_ops::SpriteorPathOp,
+  png::{encode_png, write_png_file, PngOptions},
   poly_ops::SpriteorPolyOp,
   rect_ops::{RectOpUnw, SpriteorRectOp},
 };
 
+//Ops are built once per sprite, boxing the rect op is not worth making it harder to construct.
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone)]
 pub enum SpriteorOperation {
   SpriteorVLineOp(SpriteorVLineOp),
   SpriteorHLineOp(SpriteorHLineOp),
+  SpriteorLineOp(SpriteorLineOp),
   SpriteorRectOp(SpriteorRectOp),
   SpriteorPolyOp(SpriteorPolyOp),
+  SpriteorPathOp(SpriteorPathOp),
+  SpriteorEllipseOp(SpriteorEllipseOp),
+  SpriteorShadowOp(SpriteorShadowOp),
+  SpriteorGlowOp(SpriteorGlowOp),
   NewLayer,
 }
+impl SpriteorOperation {
+  pub(crate) fn validate(&self) -> Result<(), SpriteorError> {
+    match self {
+      SpriteorOperation::SpriteorVLineOp(op) => op.validate(),
+      SpriteorOperation::SpriteorHLineOp(op) => op.validate(),
+      SpriteorOperation::SpriteorLineOp(op) => op.validate(),
+      SpriteorOperation::SpriteorRectOp(op) => op.validate(),
+      SpriteorOperation::SpriteorPolyOp(op) => op.validate(),
+      SpriteorOperation::SpriteorPathOp(op) => op.validate(),
+      SpriteorOperation::SpriteorEllipseOp(op) => op.validate(),
+      SpriteorOperation::SpriteorShadowOp(op) => op.validate(),
+      SpriteorOperation::SpriteorGlowOp(op) => op.validate(),
+      SpriteorOperation::NewLayer => Ok(()),
+    }
+  }
+  /** Shadow and glow ops as a shadow, None for everything else. */
+  fn as_effect(&self) -> Option<SpriteorShadowOp> {
+    match self {
+      SpriteorOperation::SpriteorShadowOp(op) => Some(op.clone()),
+      SpriteorOperation::SpriteorGlowOp(op) => Some(op.as_shadow()),
+      _ => None,
+    }
+  }
+  /** Draws the operation and moves into the new container for rect ops and new layers. Effect ops are drawn together with the op before them and do nothing here. */
+  fn draw(
+    &self,
+    values: &mut [u8],
+    current_rect: &mut RectOpUnw,
+    settings: (&u16, &u16, &u16),
+  ) -> Result<(), SpriteorError> {
+    let (width, height, margin) = settings;
+    if current_rect.border_box_is_empty() && !matches!(self, SpriteorOperation::NewLayer) {
+      return Ok(());
+    }
+    match self {
+      SpriteorOperation::SpriteorRectOp(rect_op) => {
+        let rect = RectOpUnw::from_rect_op(
+          rect_op,
+          current_rect.border_box_top,
+          current_rect.border_box_right,
+          current_rect.border_box_bottom,
+          current_rect.border_box_left,
+        )?;
+        rect.add_to(values, current_rect, width);
+        *current_rect = rect;
+      }
+      SpriteorOperation::SpriteorHLineOp(hline_op) => {
+        hline_op.add_to(values, current_rect, width);
+      }
+      SpriteorOperation::SpriteorLineOp(line_op) => {
+        line_op.add_to(values, current_rect, width);
+      }
+      SpriteorOperation::SpriteorPolyOp(poly_op) => {
+        poly_op.add_to(values, current_rect, width);
+      }
+      SpriteorOperation::SpriteorPathOp(path_op) => {
+        path_op.add_to(values, current_rect, width);
+      }
+      SpriteorOperation::SpriteorEllipseOp(ellipse_op) => {
+        ellipse_op.add_to(values, current_rect, width);
+      }
+      SpriteorOperation::SpriteorVLineOp(vline_op) => {
+        vline_op.add_to(values, current_rect, width);
+      }
+      SpriteorOperation::SpriteorShadowOp(_) | SpriteorOperation::SpriteorGlowOp(_) => {}
+      SpriteorOperation::NewLayer => {
+        *current_rect = RectOpUnw::empty(width, height, margin);
+      }
+    }
+    Ok(())
+  }
+}
 
+#[derive(Debug, Clone)]
 pub struct SpriteorSettings {
-  width: u16,
-  height: u16,
-  margin: u16,
-  background_color: Option<[u8; 4]>,
+  /** Sprite width in pixels, must be in range [8, 4096]. */
+  pub width: u16,
+  /** Sprite height in pixels, must be in range [8, 4096]. */
+  pub height: u16,
+  /** Empty space around the sprite, clamped so at least a 4x4 area remains inside it. */
+  pub margin: u16,
+  /** Color every pixel starts out as, including the margin. Transparent if None. */
+  pub background_color: Option<[u8; 4]>,
+}
+impl SpriteorSettings {
+  pub fn builder() -> SpriteorSettingsBuilder {
+    SpriteorSettingsBuilder::default()
+  }
 }
 impl Default for SpriteorSettings {
   fn default() -> Self {
@@ -31,6 +128,49 @@ impl Default for SpriteorSettings {
   }
 }
 
+/**
+Chainable alternative to struct update syntax for [`SpriteorSettings`], starts from the default settings.
+
+```
+use ui_spriteor::SpriteorSettings;
+
+let settings = SpriteorSettings::builder()
+  .size(64, 16)
+  .margin(2)
+  .background_color([0, 0, 0, 255])
+  .build();
+assert_eq!(settings.width, 64);
+```
+*/
+#[derive(Debug, Clone, Default)]
+pub struct SpriteorSettingsBuilder {
+  settings: SpriteorSettings,
+}
+impl SpriteorSettingsBuilder {
+  pub fn width(mut self, width: u16) -> Self {
+    self.settings.width = width;
+    self
+  }
+  pub fn height(mut self, height: u16) -> Self {
+    self.settings.height = height;
+    self
+  }
+  pub fn size(self, width: u16, height: u16) -> Self {
+    self.width(width).height(height)
+  }
+  pub fn margin(mut self, margin: u16) -> Self {
+    self.settings.margin = margin;
+    self
+  }
+  pub fn background_color(mut self, color: [u8; 4]) -> Self {
+    self.settings.background_color = Some(color);
+    self
+  }
+  pub fn build(self) -> SpriteorSettings {
+    self.settings
+  }
+}
+
 pub struct Spriteor {
   width: u16,
   height: u16,
@@ -55,44 +195,68 @@ impl Spriteor {
   pub fn new_layer(&mut self) {
     self.ops.push(SpriteorOperation::NewLayer);
   }
+  /** Draws all operations and returns the RGBA values, panics on invalid operations. See [`Spriteor::try_finalize`]. */
   pub fn finalize(&mut self) -> &Vec<u8> {
-    let mut current_rect = RectOpUnw::from_rect_op(
-      &SpriteorRectOp {
-        ..Default::default()
-      },
-      self.margin,
-      self.width - self.margin - 1,
-      self.height - self.margin - 1,
-      self.margin,
-    );
+    match self.try_finalize() {
+      Ok(values) => values,
+      Err(e) => panic!("{}", e),
+    }
+  }
+  /** Draws all operations and returns the RGBA values. If an operation is invalid nothing is drawn and the error is returned. */
+  pub fn try_finalize(&mut self) -> Result<&Vec<u8>, SpriteorError> {
     for op in &self.ops {
-      match op {
-        SpriteorOperation::SpriteorRectOp(rect_op) => {
-          let rect = RectOpUnw::from_rect_op(
-            &rect_op,
-            current_rect.border_box_top,
-            current_rect.border_box_right,
-            current_rect.border_box_bottom,
-            current_rect.border_box_left,
+      op.validate()?;
+    }
+    let mut values = self.values.clone();
+    let mut current_rect = RectOpUnw::empty(&self.width, &self.height, &self.margin);
+    let settings = (&self.width, &self.height, &self.margin);
+    for (i, op) in self.ops.iter().enumerate() {
+      let effects: Vec<SpriteorShadowOp> = self.ops[i + 1..]
+        .iter()
+        .map_while(|op| op.as_effect())
+        .collect();
+      if effects.is_empty() || matches!(op, SpriteorOperation::NewLayer) || op.as_effect().is_some()
+      {
+        op.draw(&mut values, &mut current_rect, settings)?;
+        continue;
+      }
+      //Draw the shape on its own so the effects can be put under it, but over everything before it.
+      let mut shape = vec![0_u8; values.len()];
+      op.draw(&mut shape, &mut current_rect, settings)?;
+      for effect in &effects {
+        effect.add_under(&mut values, &shape, &self.width);
+      }
+      for (pixel, color) in shape.chunks(4).enumerate() {
+        if color[3] > 0 {
+          add_color_set_pixel(
+            &mut values,
+            &pixel,
+            &[color[0], color[1], color[2], color[3]],
           );
-          rect.add_to(&mut self.values, &current_rect, &self.width);
-          current_rect = rect;
-        }
-        SpriteorOperation::SpriteorHLineOp(hline_op) => {
-          hline_op.add_to(&mut self.values, &current_rect, &self.width);
-        }
-        SpriteorOperation::SpriteorPolyOp(poly_op) => {
-          poly_op.add_to(&mut self.values, &current_rect);
-        }
-        SpriteorOperation::SpriteorVLineOp(vline_op) => {
-          vline_op.add_to(&mut self.values, &current_rect, &self.width);
-        }
-        SpriteorOperation::NewLayer => {
-          current_rect = RectOpUnw::empty(&self.width, &self.height, &self.margin);
         }
       }
     }
-    &self.values
+    self.values = values;
+    Ok(&self.values)
+  }
+  /** Encodes the current values as a PNG, call [`Spriteor::finalize`] first. */
+  pub fn to_png(&self) -> Vec<u8> {
+    self.to_png_with_options(&PngOptions::default())
+  }
+  pub fn to_png_with_options(&self, options: &PngOptions) -> Vec<u8> {
+    encode_png(&self.values, self.width, self.height, options)
+      .expect("Values always hold 4 bytes for every pixel of the sprite.")
+  }
+  /** Writes the current values to a PNG file, call [`Spriteor::finalize`] first. */
+  pub fn write_png<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+    self.write_png_with_options(path, &PngOptions::default())
+  }
+  pub fn write_png_with_options<P: AsRef<Path>>(
+    &self,
+    path: P,
+    options: &PngOptions,
+  ) -> io::Result<()> {
+    write_png_file(path, &self.values, self.width, self.height, options)
   }
   pub fn print(&self, mode: u8) {
     print_matrix(&self.values, self.width, mode);
@@ -100,35 +264,51 @@ impl Spriteor {
   pub fn add_operation(&mut self, operation: SpriteorOperation) {
     self.ops.push(operation);
   }
+  /** Same as [`Spriteor::add_operation`] but the operation is rejected if it is invalid on its own. */
+  pub fn try_add_operation(&mut self, operation: SpriteorOperation) -> Result<(), SpriteorError> {
+    operation.validate()?;
+    self.ops.push(operation);
+    Ok(())
+  }
+  /** Panics if the size is invalid. See [`Spriteor::try_new`]. */
   pub fn new(settings: &SpriteorSettings) -> Spriteor {
+    match Spriteor::try_new(settings) {
+      Ok(spriteor) => spriteor,
+      Err(e) => panic!("{}", e),
+    }
+  }
+  pub fn try_new(settings: &SpriteorSettings) -> Result<Spriteor, SpriteorError> {
     // if settings.width % 2 != 0 || settings.height % 2 != 0 {
     //   panic!("Input width and height not divisble by 2.");
     // }
-    if settings.height < 8 || settings.height > 4096 || settings.width < 8 || settings.width > 4096
-    {
-      panic!("Invalid sizing, width and height must be in range [8, 4096].")
+    let size_range = SIZE_RANGE.0..=SIZE_RANGE.1;
+    if !size_range.contains(&settings.width) || !size_range.contains(&settings.height) {
+      return Err(SpriteorError::InvalidSize {
+        width: settings.width,
+        height: settings.height,
+      });
     }
 
     let margin_max = ((settings.width / 2) - 2).min((settings.height / 2) - 2);
     let margin = settings.margin.min(margin_max);
 
     let values = if let Some(bg_color) = settings.background_color {
-      vec![0 as u8; settings.width as usize * settings.height as usize * 4]
+      vec![0_u8; settings.width as usize * settings.height as usize * 4]
         .iter()
         .enumerate()
         .map(|(i, &_x)| bg_color[i % 4])
         .collect()
     } else {
-      vec![0 as u8; settings.width as usize * settings.height as usize * 4]
+      vec![0_u8; settings.width as usize * settings.height as usize * 4]
     };
 
-    Spriteor {
+    Ok(Spriteor {
       width: settings.width,
       height: settings.height,
       margin,
       values,
       ops: Vec::new(),
-    }
+    })
   }
 }
 
@@ -136,8 +316,16 @@ impl Spriteor {
 mod tests {
   use super::*;
   use crate::{
-    debug::{modify_pixels, pixels_to_values, print_matrix},
-    poly_ops::{HEXAGON_POLY, OCTAGON_POLY},
+    border_style::{BorderStyle, StrokeAlign, StrokeCap, StrokeJoin},
+    debug::{drawn, modify_pixels, pixels_to_values, print_matrix, render_rows, white_or_color},
+    maths::xy_to_i,
+    paint::Paint,
+    path_ops::PathCommand,
+    poly_ops::{
+      PolyFit, PolyTransform, TileStagger, FIVESTAR_POLY, HEXAGON_POLY, OCTAGON_POLY, SQUARE_POLY,
+      TRIANGLE_POLY,
+    },
+    raster::FillRule,
   };
 
   #[test]
@@ -150,7 +338,7 @@ mod tests {
     print_matrix(result, 32, 2);
     assert_eq!(
       result,
-      &pixels_to_values(&vec![1 as u8; 32 * 32], &[255, 255, 0, 255])
+      &pixels_to_values(&vec![1_u8; 32 * 32], &[255, 255, 0, 255])
     );
   }
   #[test]
@@ -160,7 +348,7 @@ mod tests {
     });
     let result = spriteor.finalize();
     print_matrix(result, 32, 2);
-    assert_eq!(result, &vec![0 as u8; 32 * 32 * 4]);
+    assert_eq!(result, &vec![0_u8; 32 * 32 * 4]);
   }
   #[test]
   fn default_box() {
@@ -174,7 +362,7 @@ mod tests {
     print_matrix(result, 32, 2);
     assert_eq!(
       result,
-      &pixels_to_values(&vec![1 as u8; 32 * 32], &[200, 200, 200, 255])
+      &pixels_to_values(&vec![1_u8; 32 * 32], &[200, 200, 200, 255])
     );
   }
   #[test]
@@ -263,6 +451,7 @@ mod tests {
     spriteor.add_operation(SpriteorOperation::SpriteorRectOp(SpriteorRectOp {
       border_width: 1,
       corner_radius: 4,
+      anti_alias: false,
       ..Default::default()
     }));
     let result = spriteor.finalize();
@@ -343,10 +532,11 @@ mod tests {
     }));
 
     spriteor.add_operation(SpriteorOperation::SpriteorRectOp(SpriteorRectOp {
-      border_color: Some([255, 0, 0, 255]),
-      fill_color: Some([0, 255, 0, 255]),
+      border_color: Some([255, 0, 0, 255].into()),
+      fill_color: Some([0, 255, 0, 255].into()),
       border_width: 1,
       corner_radius: 2,
+      anti_alias: false,
       ..Default::default()
     }));
     let result = spriteor.finalize();
@@ -412,11 +602,12 @@ mod tests {
       ..Default::default()
     });
     spriteor.add_operation(SpriteorOperation::SpriteorPolyOp(SpriteorPolyOp {
+      anti_alias: false,
       ..Default::default()
     }));
     let result = spriteor.finalize();
 
-    let mut values = vec![0 as u8; 8 * 8 * 4];
+    let mut values = vec![0_u8; 8 * 8 * 4];
     let pixels = vec![
       0, 0, 0, 0, 1, 0, 0, 0, //7
       0, 0, 0, 1, 1, 0, 0, 0, //15
@@ -440,18 +631,19 @@ mod tests {
   #[rustfmt::skip]
   #[test]
   fn default_poly_on_15x15() {
-    let size = 15 as u16;
+    let size = 15_u16;
     let mut spriteor = Spriteor::new(&SpriteorSettings {
       width: size,
       height: size,
       ..Default::default()
     });
     spriteor.add_operation(SpriteorOperation::SpriteorPolyOp(SpriteorPolyOp {
+      anti_alias: false,
       ..Default::default()
     }));
     let result = spriteor.finalize();
 
-    let mut values = vec![0 as u8; size as usize * size as usize * 4];
+    let mut values = vec![0_u8; size as usize * size as usize * 4];
     let pixels = vec![
       0,0,0,0,0,0,0,1,0,0,0,0,0,0,0,
       0,0,0,0,0,0,1,1,1,0,0,0,0,0,0,
@@ -482,7 +674,7 @@ mod tests {
   #[rustfmt::skip]
   #[test]
   fn octagon_poly_on_16x16() {
-    let size = 16 as u16;
+    let size = 16_u16;
     let mut spriteor = Spriteor::new(&SpriteorSettings {
       width: size,
       height: size,
@@ -490,11 +682,12 @@ mod tests {
     });
     spriteor.add_operation(SpriteorOperation::SpriteorPolyOp(SpriteorPolyOp {
       polygon: OCTAGON_POLY.to_vec(),
+      anti_alias: false,
       ..Default::default()
     }));
     let result = spriteor.finalize();
 
-    let mut values = vec![0 as u8; size as usize * size as usize * 4];
+    let mut values = vec![0_u8; size as usize * size as usize * 4];
     let pixels = vec![
       0,0,0,0,0,1,1,1,1,1,1,0,0,0,0,0,
       0,0,0,0,1,1,1,1,1,1,1,1,0,0,0,0,
@@ -526,7 +719,7 @@ mod tests {
   #[rustfmt::skip]
   #[test]
   fn hexagon_poly_on_8x8() {
-    let size = 16 as u16;
+    let size = 16_u16;
     let mut spriteor = Spriteor::new(&SpriteorSettings {
       width: size,
       height: size,
@@ -534,22 +727,31 @@ mod tests {
     });
     spriteor.add_operation(SpriteorOperation::SpriteorPolyOp(SpriteorPolyOp {
       polygon: HEXAGON_POLY.to_vec(),
+      anti_alias: false,
       ..Default::default()
     }));
     let result = spriteor.finalize();
 
-    let mut values = vec![0 as u8; size as usize * size as usize * 4];
-    // let pixels = vec![
-    //   0,0,1,1,1,1,0,0,
-    //   0,0,1,1,1,1,0,0,
-    //   0,1,1,1,1,1,1,0,
-    //   1,1,1,1,1,1,1,1,
-    //   0,1,1,1,1,1,1,0,
-    //   0,0,1,1,1,1,0,0,
-    //   0,0,0,1,1,0,0,0,
-    //   0,0,0,0,1,0,0,0,
-    // ];
-    // modify_pixels(&mut values, &pixels, &[200, 200, 200, 255]);
+    let mut values = vec![0_u8; size as usize * size as usize * 4];
+    let pixels = vec![
+      0,0,0,0,0,1,1,1,1,1,1,0,0,0,0,0,
+      0,0,0,0,0,1,1,1,1,1,1,0,0,0,0,0,
+      0,0,0,0,1,1,1,1,1,1,1,1,0,0,0,0,
+      0,0,0,0,1,1,1,1,1,1,1,1,1,0,0,0,
+      0,0,0,1,1,1,1,1,1,1,1,1,1,0,0,0,
+      0,0,1,1,1,1,1,1,1,1,1,1,1,1,0,0,
+      0,0,1,1,1,1,1,1,1,1,1,1,1,1,1,0,
+      0,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,
+      1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,
+      0,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,
+      0,0,1,1,1,1,1,1,1,1,1,1,1,1,1,0,
+      0,0,1,1,1,1,1,1,1,1,1,1,1,1,0,0,
+      0,0,0,1,1,1,1,1,1,1,1,1,1,0,0,0,
+      0,0,0,0,1,1,1,1,1,1,1,1,1,0,0,0,
+      0,0,0,0,1,1,1,1,1,1,1,1,0,0,0,0,
+      0,0,0,0,0,1,1,1,1,1,1,0,0,0,0,0,
+    ];
+    modify_pixels(&mut values, &pixels, &[200, 200, 200, 255]);
 
 
     print_matrix(result, size, 2);
@@ -559,6 +761,712 @@ mod tests {
     );
   }
 
+  #[rustfmt::skip]
+  #[test]
+  fn outside_border_on_square_poly() {
+    let size = 12_u16;
+    let mut spriteor = Spriteor::new(&SpriteorSettings {
+      width: size,
+      height: size,
+      margin: 2,
+      ..Default::default()
+    });
+    spriteor.add_operation(SpriteorOperation::SpriteorPolyOp(SpriteorPolyOp {
+      polygon: SQUARE_POLY.to_vec(),
+      border_thickness: 1,
+      stroke_align: StrokeAlign::Outside,
+      anti_alias: false,
+      ..Default::default()
+    }));
+    let result = spriteor.finalize();
+
+    //The fill covers the whole area inside of the margin and the border is drawn in the margin.
+    let mut values = vec![0_u8; size as usize * size as usize * 4];
+    let border = vec![
+      0,0,0,0,0,0,0,0,0,0,0,0,
+      0,1,1,1,1,1,1,1,1,1,1,0,
+      0,1,0,0,0,0,0,0,0,0,1,0,
+      0,1,0,0,0,0,0,0,0,0,1,0,
+      0,1,0,0,0,0,0,0,0,0,1,0,
+      0,1,0,0,0,0,0,0,0,0,1,0,
+      0,1,0,0,0,0,0,0,0,0,1,0,
+      0,1,0,0,0,0,0,0,0,0,1,0,
+      0,1,0,0,0,0,0,0,0,0,1,0,
+      0,1,0,0,0,0,0,0,0,0,1,0,
+      0,1,1,1,1,1,1,1,1,1,1,0,
+      0,0,0,0,0,0,0,0,0,0,0,0,
+    ];
+    let fill: Vec<u8> = (0..size * size)
+      .map(|i| ((2..10).contains(&(i % size)) && (2..10).contains(&(i / size))) as u8)
+      .collect();
+    modify_pixels(&mut values, &border, &[255, 255, 255, 255]);
+    modify_pixels(&mut values, &fill, &[200, 200, 200, 255]);
+
+    print_matrix(result, size, 2);
+    assert_eq!(
+      result,
+      &values
+    );
+  }
+
+  /** Draws the poly op without anti-aliasing on a transparent sprite, border pixels are '#' and fill pixels '.'. */
+  fn poly_rows(op: SpriteorPolyOp, size: u16, margin: u16) -> Vec<String> {
+    let settings = SpriteorSettings {
+      width: size,
+      height: size,
+      margin,
+      ..Default::default()
+    };
+    let op = SpriteorPolyOp {
+      anti_alias: false,
+      ..op
+    };
+    render_rows(
+      &settings,
+      vec![SpriteorOperation::SpriteorPolyOp(op)],
+      white_or_color,
+    )
+  }
+
+  #[rustfmt::skip]
+  #[test]
+  fn fivestar_border_follows_every_edge() {
+    let rows = poly_rows(SpriteorPolyOp {
+      polygon: FIVESTAR_POLY.to_vec(),
+      border_thickness: 2,
+      ..Default::default()
+    }, 24, 2);
+    assert_eq!(rows[3..22], [
+      "           ##           ",
+      "           ##           ",
+      "           ##           ",
+      "          ####          ",
+      "        ########        ",
+      "     ##############     ",
+      "  #######......#######  ",
+      "   ####..........####   ",
+      "     ###........###     ",
+      "      ###......###      ",
+      "       ###....###       ",
+      "        ##....##        ",
+      "        ##.##.##        ",
+      "        ########        ",
+      "        ###  ###        ",
+      "       ###    ###       ",
+      "       ##      ##       ",
+      "       #        #       ",
+      "                        ",
+    ]);
+  }
+  #[rustfmt::skip]
+  #[test]
+  fn outside_poly_border_joins() {
+    let corner = |stroke_join: StrokeJoin| poly_rows(SpriteorPolyOp {
+      polygon: SQUARE_POLY.to_vec(),
+      border_thickness: 2,
+      stroke_align: StrokeAlign::Outside,
+      stroke_join,
+      ..Default::default()
+    }, 12, 2)[..3].iter().map(|row| row[..4].to_string()).collect::<Vec<String>>();
+    assert_eq!(corner(StrokeJoin::Mitre), ["####", "####", "##.."]);
+    assert_eq!(corner(StrokeJoin::Round), [" ###", "####", "##.."]);
+    assert_eq!(corner(StrokeJoin::Bevel), ["  ##", " ###", "##.."]);
+  }
+
+  #[rustfmt::skip]
+  #[test]
+  fn poly_placed_between_points() {
+    let placed = |fit: PolyFit, align: (f32, f32)| poly_rows(SpriteorPolyOp {
+      point_a: Some((1, 2)),
+      point_b: Some((-2, -3)),
+      polygon: TRIANGLE_POLY.to_vec(),
+      fit,
+      align,
+      border_thickness: 1,
+      ..Default::default()
+    }, 10, 0);
+    assert_eq!(placed(PolyFit::Stretch, (0.5, 0.5)), [
+      "          ",
+      "          ",
+      "    #     ",
+      "   ###    ",
+      "   #.#    ",
+      "  #...#   ",
+      " #######  ",
+      "          ",
+      "          ",
+      "          ",
+    ]);
+    //The 7 x 5 area fits a 5 x 5 triangle, centered it starts at x 2.
+    assert_eq!(placed(PolyFit::Contain, (0.5, 0.5)), [
+      "          ",
+      "          ",
+      "    #     ",
+      "    #     ",
+      "   #.#    ",
+      "   #.#    ",
+      "  #####   ",
+      "          ",
+      "          ",
+      "          ",
+    ]);
+    assert_eq!(placed(PolyFit::Contain, (0.0, 0.0))[6], " #####    ");
+    //A 7 x 7 triangle aligned to the bottom loses its top, which has no border where it is cut.
+    assert_eq!(placed(PolyFit::Cover, (0.5, 1.0)), [
+      "          ",
+      "          ",
+      "   #.#    ",
+      "   #.#    ",
+      "  #...#   ",
+      "  #...#   ",
+      " #######  ",
+      "          ",
+      "          ",
+      "          ",
+    ]);
+    assert_eq!(placed(PolyFit::Cover, (0.5, 0.0))[6], "  #...#   ");
+  }
+
+  #[rustfmt::skip]
+  #[test]
+  fn tile_gap_padding_and_centering() {
+    let squares = |padding: u16, center_tiles: bool| poly_rows(SpriteorPolyOp {
+      x_count: 3,
+      y_count: 2,
+      tile_gap: (1, 1),
+      padding,
+      center_tiles,
+      polygon: SQUARE_POLY.to_vec(),
+      border_thickness: 1,
+      ..Default::default()
+    }, 12, 0);
+    //12 pixels fit 3 tiles of 3 with 2 gaps, the pixel left over is on the right.
+    assert_eq!(squares(0, false)[..6], [
+      "### ### ### ",
+      "#.# #.# #.# ",
+      "#.# #.# #.# ",
+      "#.# #.# #.# ",
+      "### ### ### ",
+      "            ",
+    ]);
+    assert_eq!(squares(1, true), [
+      "            ",
+      "  ## ## ##  ",
+      "  ## ## ##  ",
+      "  ## ## ##  ",
+      "  ## ## ##  ",
+      "            ",
+      "  ## ## ##  ",
+      "  ## ## ##  ",
+      "  ## ## ##  ",
+      "  ## ## ##  ",
+      "            ",
+      "            ",
+    ]);
+  }
+  #[rustfmt::skip]
+  #[test]
+  fn staggered_tiles() {
+    let staggered = |stagger: TileStagger, tile_gap: (u16, u16)| poly_rows(SpriteorPolyOp {
+      x_count: 3,
+      y_count: 3,
+      tile_gap,
+      stagger,
+      polygon: SQUARE_POLY.to_vec(),
+      border_thickness: 1,
+      ..Default::default()
+    }, 12, 0);
+    assert_eq!(staggered(TileStagger::Rows, (1, 0)), [
+      "### ### ### ",
+      "#.# #.# #.# ",
+      "#.# #.# #.# ",
+      "### ### ### ",
+      "# ### ### # ",
+      "# #.# #.# # ",
+      "# #.# #.# # ",
+      "# ### ### # ",
+      "### ### ### ",
+      "#.# #.# #.# ",
+      "#.# #.# #.# ",
+      "### ### ### ",
+    ]);
+    assert_eq!(staggered(TileStagger::Columns, (0, 1)), [
+      "############",
+      "#..#    #..#",
+      "############",
+      "    #..#    ",
+      "############",
+      "#..#    #..#",
+      "############",
+      "    #..#    ",
+      "############",
+      "#..#    #..#",
+      "############",
+      "            ",
+    ]);
+  }
+
+  #[rustfmt::skip]
+  #[test]
+  fn transformed_poly_tiles() {
+    let triangles = |x_count: u16, transform: PolyTransform, alternate_transform: Option<PolyTransform>| {
+      poly_rows(SpriteorPolyOp {
+        x_count,
+        y_count: x_count,
+        polygon: TRIANGLE_POLY.to_vec(),
+        transform,
+        alternate_transform,
+        border_thickness: 1,
+        ..Default::default()
+      }, 8, 0)
+    };
+    let rotated = |rotation: f32| PolyTransform { rotation, ..Default::default() };
+    assert_eq!(triangles(1, rotated(90.0), None), [
+      "#       ",
+      "###     ",
+      "#..##   ",
+      "#....## ",
+      "#....## ",
+      "#..##   ",
+      "###     ",
+      "#       ",
+    ]);
+    let shrunk = PolyTransform {
+      flip_y: true,
+      scale: (0.5, 0.5),
+      translate: (0.25, 0.0),
+      ..Default::default()
+    };
+    assert_eq!(triangles(1, shrunk, None)[1..6], [
+      "        ",
+      "    ####",
+      "     ## ",
+      "     ## ",
+      "        ",
+    ]);
+    assert_eq!(triangles(2, PolyTransform::default(), Some(rotated(180.0))), [
+      "    ####",
+      " ##  ## ",
+      " ##  ## ",
+      "####    ",
+      "####    ",
+      " ##  ## ",
+      " ##  ## ",
+      "    ####",
+    ]);
+  }
+
+  #[rustfmt::skip]
+  #[test]
+  fn poly_contours_cut_holes() {
+    let frame = |fill_rule: FillRule| poly_rows(SpriteorPolyOp {
+      polygon: SQUARE_POLY.to_vec(),
+      contours: vec![vec![(0.3, 0.3), (0.7, 0.3), (0.7, 0.7), (0.3, 0.7)]],
+      fill_rule,
+      border_thickness: 1,
+      ..Default::default()
+    }, 10, 0);
+    assert_eq!(frame(FillRule::EvenOdd), [
+      "##########",
+      "#........#",
+      "#.######.#",
+      "#.#    #.#",
+      "#.#    #.#",
+      "#.#    #.#",
+      "#.#    #.#",
+      "#.######.#",
+      "#........#",
+      "##########",
+    ]);
+    //Both outlines go clockwise, so non-zero fills the inner one and still draws its border.
+    assert_eq!(frame(FillRule::NonZero)[3..7], [
+      "#.######.#",
+      "#.##..##.#",
+      "#.##..##.#",
+      "#.######.#",
+    ]);
+  }
+
+  fn ten_by_ten() -> SpriteorSettings {
+    SpriteorSettings {
+      width: 10,
+      height: 10,
+      ..Default::default()
+    }
+  }
+
+  /** Draws the line op without anti-aliasing on a transparent 10 x 10 sprite, line pixels are '#'. */
+  fn line_rows(op: SpriteorLineOp) -> Vec<String> {
+    let op = SpriteorLineOp {
+      anti_alias: false,
+      ..op
+    };
+    render_rows(
+      &ten_by_ten(),
+      vec![SpriteorOperation::SpriteorLineOp(op)],
+      drawn,
+    )
+  }
+
+  #[rustfmt::skip]
+  #[test]
+  fn diagonal_line() {
+    assert_eq!(line_rows(SpriteorLineOp {
+      points: vec![(1, 1), (-1, -1)],
+      ..Default::default()
+    }), [
+      "          ",
+      " #        ",
+      "  #       ",
+      "   #      ",
+      "    #     ",
+      "     #    ",
+      "      #   ",
+      "       #  ",
+      "        # ",
+      "          ",
+    ]);
+  }
+  #[rustfmt::skip]
+  #[test]
+  fn polyline_caps_and_joins() {
+    let corner = |join: StrokeJoin, cap: StrokeCap| line_rows(SpriteorLineOp {
+      points: vec![(2, 8), (2, 2), (8, 2)],
+      width: 3,
+      join,
+      cap,
+      ..Default::default()
+    });
+    assert_eq!(corner(StrokeJoin::Mitre, StrokeCap::Square), [
+      "          ",
+      " #########",
+      " #########",
+      " #########",
+      " ###      ",
+      " ###      ",
+      " ###      ",
+      " ###      ",
+      " ###      ",
+      " ###      ",
+    ]);
+    //Butt caps end at the centers of the end pixels, which are on the edge and left out.
+    assert_eq!(corner(StrokeJoin::Bevel, StrokeCap::Butt), [
+      "          ",
+      "  ######  ",
+      " #######  ",
+      " #######  ",
+      " ###      ",
+      " ###      ",
+      " ###      ",
+      " ###      ",
+      "          ",
+      "          ",
+    ]);
+  }
+  #[test]
+  fn anti_aliased_line() {
+    let mut spriteor = Spriteor::new(&SpriteorSettings {
+      width: 10,
+      height: 10,
+      ..Default::default()
+    });
+    spriteor.add_operation(SpriteorOperation::SpriteorLineOp(SpriteorLineOp {
+      points: vec![(1, 2), (8, 5)],
+      color: Some(Paint::Solid([255, 0, 0, 255])),
+      ..Default::default()
+    }));
+    let result = spriteor.finalize();
+    let alpha = |x: usize, y: usize| result[(y * 10 + x) * 4 + 3];
+    assert_eq!(alpha(1, 2), 255);
+    assert_eq!(alpha(8, 5), 255);
+    //Halfway the line passes between the centers of two pixels and covers both partly.
+    assert!(alpha(4, 3) > 0 && alpha(4, 3) < 255 && alpha(4, 4) > 0 && alpha(4, 4) < 255);
+    assert_eq!(alpha(1, 5), 0);
+  }
+
+  #[test]
+  fn widest_line_covers_the_sprite() {
+    let rows = line_rows(SpriteorLineOp {
+      points: vec![(0, 0), (-1, -1)],
+      width: 4096,
+      ..Default::default()
+    });
+    assert!(rows.iter().all(|row| row == "##########"));
+  }
+
+  #[rustfmt::skip]
+  #[test]
+  fn line_segments_and_patterns() {
+    let operations = vec![
+      SpriteorOperation::SpriteorHLineOp(SpriteorHLineOp {
+        y: -1,
+        start: Some(2),
+        end: Some(-3),
+        ..Default::default()
+      }),
+      SpriteorOperation::SpriteorHLineOp(SpriteorHLineOp {
+        width: 2,
+        centered: true,
+        style: BorderStyle::Dashed { dash: 2, gap: 1 },
+        ..Default::default()
+      }),
+      SpriteorOperation::SpriteorVLineOp(SpriteorVLineOp {
+        x: -1,
+        start: Some(0),
+        end: Some(-3),
+        style: BorderStyle::Dotted { gap: 1 },
+        ..Default::default()
+      }),
+      SpriteorOperation::SpriteorHLineOp(SpriteorHLineOp {
+        y: 1,
+        width: 2,
+        start: Some(1),
+        end: Some(-2),
+        style: BorderStyle::Groove,
+        color: Some(Paint::Solid([128, 128, 128, 255])),
+        ..Default::default()
+      }),
+    ];
+    let rows = render_rows(&ten_by_ten(), operations, |pixel| match pixel {
+      [_, _, _, 0] => ' ',
+      [255, 255, 255, _] => '#',
+      [red, ..] if *red < 128 => 'd',
+      _ => 'l',
+    });
+    //Negative positions end one pixel before the far edge, the same as the points of rect ops.
+    assert_eq!(rows, [
+      "        # ",
+      " ddddddd  ",
+      " lllllll# ",
+      "          ",
+      "# ## ## ##",
+      "# ## ## ##",
+      "        # ",
+      "          ",
+      "  #####   ",
+      "          ",
+    ]);
+  }
+
+  #[test]
+  fn line_ops_resolve_points_alike() {
+    let settings = SpriteorSettings {
+      width: 16,
+      height: 16,
+      margin: 4,
+      ..Default::default()
+    };
+    let rows = |operation: SpriteorOperation| render_rows(&settings, vec![operation], drawn);
+    let line = |points: Vec<(i16, i16)>| {
+      rows(SpriteorOperation::SpriteorLineOp(SpriteorLineOp {
+        points,
+        anti_alias: false,
+        ..Default::default()
+      }))
+    };
+    //Inside a margin positive values are clamped to the container and negative ones counted back from its size.
+    let h_line = rows(SpriteorOperation::SpriteorHLineOp(SpriteorHLineOp {
+      y: 1,
+      start: Some(1),
+      end: Some(-2),
+      ..Default::default()
+    }));
+    assert_eq!(h_line[4], "    ##          ");
+    assert_eq!(h_line, line(vec![(1, 1), (-2, 1)]));
+    let v_line = rows(SpriteorOperation::SpriteorVLineOp(SpriteorVLineOp {
+      x: 6,
+      start: Some(-5),
+      end: Some(9),
+      ..Default::default()
+    }));
+    assert!((4..=9).all(|y| v_line[y] == "      #         "));
+    assert_eq!(v_line, line(vec![(6, -5), (6, 9)]));
+  }
+
+  /** Draws the path op without anti-aliasing on a transparent 10 x 10 sprite, white pixels are '#' and others '.'. */
+  fn path_rows(op: SpriteorPathOp) -> Vec<String> {
+    let op = SpriteorPathOp {
+      anti_alias: false,
+      ..op
+    };
+    render_rows(
+      &ten_by_ten(),
+      vec![SpriteorOperation::SpriteorPathOp(op)],
+      white_or_color,
+    )
+  }
+
+  #[rustfmt::skip]
+  #[test]
+  fn path_curves() {
+    //Half circle over the bottom of the area from (1, 1) to (7, 7), filled without a line.
+    assert_eq!(path_rows(SpriteorPathOp {
+      point_a: Some((1, 1)),
+      point_b: Some((-2, -2)),
+      commands: vec![
+        PathCommand::MoveTo(0.0, 1.0),
+        PathCommand::ArcTo { radii: (0.5, 0.5), rotation: 0.0, large_arc: false, sweep: true, to: (1.0, 1.0) },
+        PathCommand::Close,
+      ],
+      fill_color: Some(Paint::Solid([255, 0, 0, 255])),
+      stroke_width: 0,
+      ..Default::default()
+    }), [
+      "          ",
+      "          ",
+      "          ",
+      "          ",
+      "          ",
+      "  .....   ",
+      " .......  ",
+      " .......  ",
+      "          ",
+      "          ",
+    ]);
+    //Open check mark of a line and a quadratic curve, only stroked.
+    assert_eq!(path_rows(SpriteorPathOp {
+      commands: vec![
+        PathCommand::MoveTo(0.15, 0.55),
+        PathCommand::LineTo(0.4, 0.8),
+        PathCommand::QuadTo { control: (0.6, 0.3), to: (0.85, 0.15) },
+      ],
+      ..Default::default()
+    }), [
+      "          ",
+      "          ",
+      "       #  ",
+      "      #   ",
+      "     #    ",
+      "     #    ",
+      "  # #     ",
+      "   ##     ",
+      "          ",
+      "          ",
+    ]);
+  }
+
+  /** Draws the ellipse op with a red fill without anti-aliasing on a transparent 10 x 10 sprite, white pixels are '#' and red '.'. */
+  fn ellipse_rows(op: SpriteorEllipseOp) -> Vec<String> {
+    let op = SpriteorEllipseOp {
+      fill_color: Some(Paint::Solid([255, 0, 0, 255])),
+      anti_alias: false,
+      ..op
+    };
+    render_rows(
+      &ten_by_ten(),
+      vec![SpriteorOperation::SpriteorEllipseOp(op)],
+      white_or_color,
+    )
+  }
+
+  #[rustfmt::skip]
+  #[test]
+  fn ellipse_rings_and_arcs() {
+    assert_eq!(ellipse_rows(SpriteorEllipseOp {
+      border_thickness: 1,
+      ..Default::default()
+    }), [
+      "   ####   ",
+      " ##....## ",
+      " #......# ",
+      "#........#",
+      "#........#",
+      "#........#",
+      "#........#",
+      " #......# ",
+      " ##....## ",
+      "   ####   ",
+    ]);
+    assert_eq!(ellipse_rows(SpriteorEllipseOp {
+      inner_ratio: 0.5,
+      ..Default::default()
+    }), [
+      "   ....   ",
+      " ........ ",
+      " ........ ",
+      "...    ...",
+      "...    ...",
+      "...    ...",
+      "...    ...",
+      " ........ ",
+      " ........ ",
+      "   ....   ",
+    ]);
+    //From the left through the top to the right, with a pie slice for the top right quarter.
+    assert_eq!(ellipse_rows(SpriteorEllipseOp {
+      inner_ratio: 0.6,
+      start_angle: 270.0,
+      end_angle: 90.0,
+      ..Default::default()
+    })[..6], [
+      "   ....   ",
+      " ........ ",
+      " ..    .. ",
+      "..      ..",
+      "..      ..",
+      "          ",
+    ]);
+    assert_eq!(ellipse_rows(SpriteorEllipseOp {
+      end_angle: 90.0,
+      ..Default::default()
+    })[..6], [
+      "     ..   ",
+      "     .... ",
+      "     .... ",
+      "     .....",
+      "     .....",
+      "          ",
+    ]);
+  }
+
+  #[test]
+  fn anti_aliased_diamond_edges() {
+    let size = 16_u16;
+    let mut spriteor = Spriteor::new(&SpriteorSettings {
+      width: size,
+      height: size,
+      ..Default::default()
+    });
+    spriteor.add_operation(SpriteorOperation::SpriteorPolyOp(SpriteorPolyOp {
+      ..Default::default()
+    }));
+    let result = spriteor.finalize();
+    print_matrix(result, size, 3);
+    let alpha = |x: u16, y: u16| result[xy_to_i(&size, &x, &y) * 4 + 3];
+    //Pixel centers on the edge are half covered, a diagonal half pixel further out or in they are empty or full.
+    assert_eq!(alpha(3, 4), 128);
+    assert_eq!(alpha(3, 3), 0);
+    assert_eq!(alpha(4, 4), 255);
+    for y in 0..size {
+      for x in 0..size {
+        assert_eq!(alpha(x, y), alpha(size - 1 - x, y));
+        assert_eq!(alpha(x, y), alpha(x, size - 1 - y));
+      }
+    }
+  }
+  #[test]
+  fn anti_aliased_poly_border_blends_into_fill() {
+    let size = 16_u16;
+    let mut spriteor = Spriteor::new(&SpriteorSettings {
+      width: size,
+      height: size,
+      ..Default::default()
+    });
+    spriteor.add_operation(SpriteorOperation::SpriteorPolyOp(SpriteorPolyOp {
+      border_thickness: 1,
+      border_color: Some(Paint::Solid([255, 0, 0, 255])),
+      fill_color: Some(Paint::Solid([0, 0, 255, 255])),
+      ..Default::default()
+    }));
+    let result = spriteor.finalize();
+    let pixel = |x: u16, y: u16| &result[xy_to_i(&size, &x, &y) * 4..][..4];
+    assert_eq!(pixel(3, 4), [255, 0, 0, 128]);
+    assert_eq!(pixel(8, 8), [0, 0, 255, 255]);
+    //1.5 pixels into the diamond is past the middle of the pixel row that is both border and fill.
+    let mixed = pixel(4, 5);
+    assert!(mixed[0] > 0 && mixed[2] > 0 && mixed[3] == 255);
+  }
+
   //Box with lines
   //Box with lines
   #[rustfmt::skip]
@@ -575,19 +1483,21 @@ mod tests {
     }));
     spriteor.add_operation(SpriteorOperation::SpriteorHLineOp(SpriteorHLineOp {
       width: 2,
-      y: 2,
+      y: 3,
       color: None,
-      // color: Some([0, 100, 0, 255]),
+      // color: Some([0, 100, 0, 255].into()),
+      ..Default::default()
     }));
     spriteor.add_operation(SpriteorOperation::SpriteorVLineOp(SpriteorVLineOp {
       width: 2,
-      x: 2,
+      x: 3,
       color: None,
-      // color: Some([0, 100, 0, 255]),
+      // color: Some([0, 100, 0, 255].into()),
+      ..Default::default()
     }));
     let result = spriteor.finalize();
 
-    let mut values = vec![0 as u8; 8 * 8 * 4];
+    let mut values = vec![0_u8; 8 * 8 * 4];
     let border = vec![
       0, 0, 0, 0, 0, 0, 0, 0,
       0, 1, 1, 1, 1, 1, 1, 0,
@@ -631,17 +1541,19 @@ mod tests {
     }));
     spriteor.add_operation(SpriteorOperation::SpriteorVLineOp(SpriteorVLineOp {
       width: 1,
-      x: 1,
-      color: Some([0, 100, 0, 255]),
+      x: 2,
+      color: Some([0, 100, 0, 255].into()),
+      ..Default::default()
     }));
     spriteor.add_operation(SpriteorOperation::SpriteorVLineOp(SpriteorVLineOp {
       width: 1,
-      x: 4,
-      color: Some([0, 100, 0, 255]),
+      x: 5,
+      color: Some([0, 100, 0, 255].into()),
+      ..Default::default()
     }));
     let result = spriteor.finalize();
 
-    let mut values = vec![0 as u8; 8 * 8 * 4];
+    let mut values = vec![0_u8; 8 * 8 * 4];
     let cross = vec![
       0, 0, 0, 0, 0, 0, 0, 0,
       0, 1, 1, 1, 1, 1, 1, 0,
@@ -685,17 +1597,19 @@ mod tests {
     }));
     spriteor.add_operation(SpriteorOperation::SpriteorHLineOp(SpriteorHLineOp {
       width: 1,
-      y: 1,
-      color: Some([0, 100, 100, 255]),
+      y: 2,
+      color: Some([0, 100, 100, 255].into()),
+      ..Default::default()
     }));
     spriteor.add_operation(SpriteorOperation::SpriteorHLineOp(SpriteorHLineOp {
       width: 1,
-      y: 4,
-      color: Some([0, 100, 100, 255]),
+      y: 5,
+      color: Some([0, 100, 100, 255].into()),
+      ..Default::default()
     }));
     let result = spriteor.finalize();
 
-    let mut values = vec![0 as u8; 8 * 8 * 4];
+    let mut values = vec![0_u8; 8 * 8 * 4];
     let cross = vec![
       0, 0, 0, 0, 0, 0, 0, 0,
       0, 1, 1, 1, 1, 1, 1, 0,
@@ -725,4 +1639,48 @@ mod tests {
       &values
     );
   }
+
+  #[rustfmt::skip]
+  #[test]
+  fn hline_is_clipped_by_rounded_container() {
+    let settings = SpriteorSettings {
+      width: 16,
+      height: 16,
+      ..Default::default()
+    };
+    let container = SpriteorOperation::SpriteorRectOp(SpriteorRectOp {
+      corner_radius: 6,
+      fill_color: Some(Paint::Solid([0, 0, 0, 0])),
+      anti_alias: false,
+      ..Default::default()
+    });
+    let line = SpriteorOperation::SpriteorHLineOp(SpriteorHLineOp {
+      y: 0,
+      ..Default::default()
+    });
+    assert_eq!(render_rows(&settings, vec![container, line], drawn)[0], "      ####      ");
+  }
+  #[test]
+  fn line_is_clipped_by_rounded_container() {
+    let settings = SpriteorSettings {
+      width: 16,
+      height: 16,
+      ..Default::default()
+    };
+    let container = SpriteorOperation::SpriteorRectOp(SpriteorRectOp {
+      corner_radius: 6,
+      fill_color: Some(Paint::Solid([0, 0, 0, 0])),
+      anti_alias: false,
+      ..Default::default()
+    });
+    let line = SpriteorOperation::SpriteorLineOp(SpriteorLineOp {
+      points: vec![(0, 0), (15, 0)],
+      anti_alias: false,
+      ..Default::default()
+    });
+    assert_eq!(
+      render_rows(&settings, vec![container, line], drawn)[0],
+      "      ####      "
+    );
+  }
 }
@@ -0,0 +1,338 @@
+use crate::{error::SpriteorError, path_ops::PathCommand};
+
+/**
+Parses the data of an SVG path `d` attribute into commands with absolute coordinates, in the units of the SVG.
+
+Supports all commands of SVG 1.1 in absolute and relative form. `H` and `V` become lines, `S` and `T` curves with the reflected control point.
+*/
+pub(crate) fn parse_svg_path(data: &str) -> Result<Vec<PathCommand>, SpriteorError> {
+  let mut parser = Parser {
+    data: data.as_bytes(),
+    index: 0,
+  };
+  let mut commands = Vec::new();
+  let (mut current, mut start) = ((0.0, 0.0), (0.0, 0.0));
+  //Second control point of the previous curve, reflected by the smooth curve commands.
+  let mut cubic_control: Option<(f32, f32)> = None;
+  let mut quad_control: Option<(f32, f32)> = None;
+  let mut command: Option<u8> = None;
+
+  loop {
+    parser.skip_whitespace();
+    let Some(next) = parser.peek() else {
+      break;
+    };
+    let letter = if next.is_ascii_alphabetic() {
+      parser.index += 1;
+      next
+    } else {
+      //Numbers repeat the previous command, after a move they are lines.
+      match command {
+        Some(b'M') => b'L',
+        Some(b'm') => b'l',
+        Some(letter) if !letter.eq_ignore_ascii_case(&b'z') => letter,
+        _ => return Err(parser.error()),
+      }
+    };
+    if command.is_none() && !letter.eq_ignore_ascii_case(&b'm') {
+      return Err(SpriteorError::InvalidPathData {
+        index: parser.index - 1,
+      });
+    }
+    command = Some(letter);
+
+    let relative = letter.is_ascii_lowercase();
+    let base = if relative { current } else { (0.0, 0.0) };
+    let point = |parser: &mut Parser| -> Result<(f32, f32), SpriteorError> {
+      let x = parser.number()?;
+      let y = parser.number()?;
+      Ok((base.0 + x, base.1 + y))
+    };
+    let reflect = |control: Option<(f32, f32)>| {
+      control.map_or(current, |c| (2.0 * current.0 - c.0, 2.0 * current.1 - c.1))
+    };
+    let (mut next_cubic, mut next_quad) = (None, None);
+    match letter.to_ascii_uppercase() {
+      b'M' => {
+        current = point(&mut parser)?;
+        start = current;
+        commands.push(PathCommand::MoveTo(current.0, current.1));
+      }
+      b'L' => {
+        current = point(&mut parser)?;
+        commands.push(PathCommand::LineTo(current.0, current.1));
+      }
+      b'H' => {
+        current.0 = base.0 + parser.number()?;
+        commands.push(PathCommand::LineTo(current.0, current.1));
+      }
+      b'V' => {
+        current.1 = base.1 + parser.number()?;
+        commands.push(PathCommand::LineTo(current.0, current.1));
+      }
+      b'C' | b'S' => {
+        let control_a = if letter.eq_ignore_ascii_case(&b'C') {
+          point(&mut parser)?
+        } else {
+          reflect(cubic_control)
+        };
+        let control_b = point(&mut parser)?;
+        current = point(&mut parser)?;
+        next_cubic = Some(control_b);
+        commands.push(PathCommand::CubicTo {
+          control_a,
+          control_b,
+          to: current,
+        });
+      }
+      b'Q' | b'T' => {
+        let control = if letter.eq_ignore_ascii_case(&b'Q') {
+          point(&mut parser)?
+        } else {
+          reflect(quad_control)
+        };
+        current = point(&mut parser)?;
+        next_quad = Some(control);
+        commands.push(PathCommand::QuadTo {
+          control,
+          to: current,
+        });
+      }
+      b'A' => {
+        let radii = (parser.number()?, parser.number()?);
+        let rotation = parser.number()?;
+        let large_arc = parser.flag()?;
+        let sweep = parser.flag()?;
+        current = point(&mut parser)?;
+        commands.push(PathCommand::ArcTo {
+          radii,
+          rotation,
+          large_arc,
+          sweep,
+          to: current,
+        });
+      }
+      b'Z' => {
+        current = start;
+        commands.push(PathCommand::Close);
+      }
+      _ => {
+        return Err(SpriteorError::InvalidPathData {
+          index: parser.index - 1,
+        })
+      }
+    }
+    cubic_control = next_cubic;
+    quad_control = next_quad;
+  }
+  Ok(commands)
+}
+
+struct Parser<'a> {
+  data: &'a [u8],
+  index: usize,
+}
+impl Parser<'_> {
+  fn peek(&self) -> Option<u8> {
+    self.data.get(self.index).copied()
+  }
+  fn error(&self) -> SpriteorError {
+    SpriteorError::InvalidPathData { index: self.index }
+  }
+  fn skip_whitespace(&mut self) {
+    while self.peek().is_some_and(|c| c.is_ascii_whitespace()) {
+      self.index += 1;
+    }
+  }
+  /** Skips whitespace and at most one comma between arguments. */
+  fn skip_separator(&mut self) {
+    self.skip_whitespace();
+    if self.peek() == Some(b',') {
+      self.index += 1;
+      self.skip_whitespace();
+    }
+  }
+  fn skip_digits(&mut self) -> usize {
+    let start = self.index;
+    while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+      self.index += 1;
+    }
+    self.index - start
+  }
+  /** Reads a number, which ends where it can't continue, so "1.5.5-2" are the three numbers 1.5, .5 and -2. */
+  fn number(&mut self) -> Result<f32, SpriteorError> {
+    self.skip_separator();
+    let start = self.index;
+    if matches!(self.peek(), Some(b'+' | b'-')) {
+      self.index += 1;
+    }
+    let mut digits = self.skip_digits();
+    if self.peek() == Some(b'.') {
+      self.index += 1;
+      digits += self.skip_digits();
+    }
+    if digits == 0 {
+      self.index = start;
+      return Err(self.error());
+    }
+    //Only an exponent if digits follow, the e could be the start of something else.
+    if matches!(self.peek(), Some(b'e' | b'E')) {
+      let mantissa_end = self.index;
+      self.index += 1;
+      if matches!(self.peek(), Some(b'+' | b'-')) {
+        self.index += 1;
+      }
+      if self.skip_digits() == 0 {
+        self.index = mantissa_end;
+      }
+    }
+    //Only ASCII was consumed, so the slice is valid UTF-8.
+    let text = std::str::from_utf8(&self.data[start..self.index]).map_err(|_| self.error())?;
+    //Numbers too large for f32 parse to infinity.
+    text
+      .parse()
+      .ok()
+      .filter(|number: &f32| number.is_finite())
+      .ok_or(SpriteorError::InvalidPathData { index: start })
+  }
+  /** Reads an arc flag, which can be written without separator before the next argument. */
+  fn flag(&mut self) -> Result<bool, SpriteorError> {
+    self.skip_separator();
+    let flag = match self.peek() {
+      Some(b'0') => false,
+      Some(b'1') => true,
+      _ => return Err(self.error()),
+    };
+    self.index += 1;
+    Ok(flag)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use PathCommand::*;
+
+  #[test]
+  fn absolute_and_relative_lines() {
+    assert_eq!(
+      parse_svg_path("M10 10 h5 v5 H10 l-2-2 3 3z m1,1 2 2 L0 0"),
+      Ok(vec![
+        MoveTo(10.0, 10.0),
+        LineTo(15.0, 10.0),
+        LineTo(15.0, 15.0),
+        LineTo(10.0, 15.0),
+        LineTo(8.0, 13.0),
+        LineTo(11.0, 16.0),
+        Close,
+        //Relative to the start of the closed subpath, the extra pair is a line.
+        MoveTo(11.0, 11.0),
+        LineTo(13.0, 13.0),
+        LineTo(0.0, 0.0),
+      ])
+    );
+  }
+  #[test]
+  fn compact_numbers() {
+    assert_eq!(
+      parse_svg_path("M1.5.5-1e1-2E-1L+3,.25e"),
+      Err(SpriteorError::InvalidPathData { index: 22 })
+    );
+    assert_eq!(
+      parse_svg_path("M1.5.5-1e1-2E-1L+3,.25"),
+      Ok(vec![
+        MoveTo(1.5, 0.5),
+        LineTo(-10.0, -0.2),
+        LineTo(3.0, 0.25)
+      ])
+    );
+  }
+  #[test]
+  fn smooth_curves_reflect_control_points() {
+    assert_eq!(
+      parse_svg_path("M0 0C1 0 2 1 2 2S3 4 4 4Q5 4 5 5T6 6t1 0s1 1 2 2"),
+      Ok(vec![
+        MoveTo(0.0, 0.0),
+        CubicTo {
+          control_a: (1.0, 0.0),
+          control_b: (2.0, 1.0),
+          to: (2.0, 2.0),
+        },
+        CubicTo {
+          control_a: (2.0, 3.0),
+          control_b: (3.0, 4.0),
+          to: (4.0, 4.0),
+        },
+        QuadTo {
+          control: (5.0, 4.0),
+          to: (5.0, 5.0),
+        },
+        QuadTo {
+          control: (5.0, 6.0),
+          to: (6.0, 6.0),
+        },
+        QuadTo {
+          control: (7.0, 6.0),
+          to: (7.0, 6.0),
+        },
+        //After a quadratic curve the first control point is the current point.
+        CubicTo {
+          control_a: (7.0, 6.0),
+          control_b: (8.0, 7.0),
+          to: (9.0, 8.0),
+        },
+      ])
+    );
+  }
+  #[test]
+  fn arcs_with_packed_flags() {
+    assert_eq!(
+      parse_svg_path("M2 2a1 1 30 011 1A2,3,0,1,0,0,0"),
+      Ok(vec![
+        MoveTo(2.0, 2.0),
+        ArcTo {
+          radii: (1.0, 1.0),
+          rotation: 30.0,
+          large_arc: false,
+          sweep: true,
+          to: (3.0, 3.0),
+        },
+        ArcTo {
+          radii: (2.0, 3.0),
+          rotation: 0.0,
+          large_arc: true,
+          sweep: false,
+          to: (0.0, 0.0),
+        },
+      ])
+    );
+    assert_eq!(
+      parse_svg_path("M0 0A1 1 0 2 0 1 1"),
+      Err(SpriteorError::InvalidPathData { index: 11 })
+    );
+  }
+  #[test]
+  fn invalid_data() {
+    assert_eq!(parse_svg_path(""), Ok(vec![]));
+    assert_eq!(
+      parse_svg_path("L1 1"),
+      Err(SpriteorError::InvalidPathData { index: 0 })
+    );
+    assert_eq!(
+      parse_svg_path("M1 1 L2"),
+      Err(SpriteorError::InvalidPathData { index: 7 })
+    );
+    assert_eq!(
+      parse_svg_path("M1 1 X2 2"),
+      Err(SpriteorError::InvalidPathData { index: 5 })
+    );
+    assert_eq!(
+      parse_svg_path("M1 1z 2 2"),
+      Err(SpriteorError::InvalidPathData { index: 6 })
+    );
+    assert_eq!(
+      parse_svg_path("M1 1 L2 1e39"),
+      Err(SpriteorError::InvalidPathData { index: 8 })
+    );
+  }
+}
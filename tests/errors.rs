@@ -0,0 +1,322 @@
+use ui_spriteor::prelude::*;
+
+fn small_spriteor() -> Spriteor {
+  Spriteor::try_new(&SpriteorSettings::builder().size(16, 16).build()).unwrap()
+}
+
+#[test]
+fn try_new_rejects_invalid_sizes() {
+  for (width, height) in [(7, 16), (16, 7), (4097, 16), (16, 4097), (0, 0)] {
+    let result = Spriteor::try_new(&SpriteorSettings::builder().size(width, height).build());
+    assert_eq!(
+      result.err(),
+      Some(SpriteorError::InvalidSize { width, height })
+    );
+  }
+}
+
+#[test]
+#[should_panic]
+fn new_still_panics_on_invalid_size() {
+  Spriteor::new(&SpriteorSettings::builder().size(4, 4).build());
+}
+
+#[test]
+fn try_add_operation_rejects_zero_tile_count() {
+  let mut spriteor = small_spriteor();
+  let result = spriteor.try_add_operation(SpriteorOperation::SpriteorPolyOp(SpriteorPolyOp {
+    x_count: 0,
+    ..Default::default()
+  }));
+  assert_eq!(
+    result,
+    Err(SpriteorError::ZeroTileCount {
+      x_count: 0,
+      y_count: 1
+    })
+  );
+}
+
+#[test]
+fn try_add_operation_rejects_out_of_range_points() {
+  let mut spriteor = small_spriteor();
+  let result = spriteor.try_add_operation(SpriteorOperation::SpriteorRectOp(SpriteorRectOp {
+    point_a: Some((0, -5000)),
+    ..Default::default()
+  }));
+  assert_eq!(
+    result,
+    Err(SpriteorError::CoordinateOutOfRange { x: 0, y: -5000 })
+  );
+  let result = spriteor.try_add_operation(SpriteorOperation::SpriteorPolyOp(SpriteorPolyOp {
+    point_b: Some((4097, 0)),
+    ..Default::default()
+  }));
+  assert_eq!(
+    result,
+    Err(SpriteorError::CoordinateOutOfRange { x: 4097, y: 0 })
+  );
+  let result = spriteor.try_add_operation(SpriteorOperation::SpriteorLineOp(SpriteorLineOp {
+    points: vec![(0, 0), (-4097, 0)],
+    ..Default::default()
+  }));
+  assert_eq!(
+    result,
+    Err(SpriteorError::CoordinateOutOfRange { x: -4097, y: 0 })
+  );
+  let result = spriteor.try_add_operation(SpriteorOperation::SpriteorPathOp(SpriteorPathOp {
+    point_a: Some((0, 5000)),
+    ..Default::default()
+  }));
+  assert_eq!(
+    result,
+    Err(SpriteorError::CoordinateOutOfRange { x: 0, y: 5000 })
+  );
+  let result =
+    spriteor.try_add_operation(SpriteorOperation::SpriteorEllipseOp(SpriteorEllipseOp {
+      point_b: Some((-4097, -4097)),
+      ..Default::default()
+    }));
+  assert_eq!(
+    result,
+    Err(SpriteorError::CoordinateOutOfRange { x: -4097, y: -4097 })
+  );
+}
+
+#[test]
+fn try_add_operation_rejects_zero_width_lines() {
+  let mut spriteor = small_spriteor();
+  let result = spriteor.try_add_operation(SpriteorOperation::SpriteorHLineOp(SpriteorHLineOp {
+    width: 0,
+    ..Default::default()
+  }));
+  assert_eq!(result, Err(SpriteorError::ZeroLineWidth));
+  let result = spriteor.try_add_operation(SpriteorOperation::SpriteorVLineOp(SpriteorVLineOp {
+    width: 0,
+    ..Default::default()
+  }));
+  assert_eq!(result, Err(SpriteorError::ZeroLineWidth));
+  let result = spriteor.try_add_operation(SpriteorOperation::SpriteorLineOp(SpriteorLineOp {
+    points: vec![(0, 0), (4, 4)],
+    width: 0,
+    ..Default::default()
+  }));
+  assert_eq!(result, Err(SpriteorError::ZeroLineWidth));
+}
+
+#[test]
+fn try_add_operation_rejects_out_of_range_values() {
+  let out_of_range = |name, value, min, max| {
+    Err(SpriteorError::ValueOutOfRange {
+      name,
+      value,
+      min,
+      max,
+    })
+  };
+  let mut spriteor = small_spriteor();
+  let result = spriteor.try_add_operation(SpriteorOperation::SpriteorLineOp(SpriteorLineOp {
+    points: vec![(0, 0), (4, 4)],
+    width: 65535,
+    ..Default::default()
+  }));
+  assert_eq!(result, out_of_range("width", 65535, 1, 4096));
+  let result = spriteor.try_add_operation(SpriteorOperation::SpriteorHLineOp(SpriteorHLineOp {
+    style: BorderStyle::Dashed {
+      dash: 4,
+      gap: 40000,
+    },
+    ..Default::default()
+  }));
+  assert_eq!(result, out_of_range("gap", 40000, 0, 4096));
+  let result = spriteor.try_add_operation(SpriteorOperation::SpriteorRectOp(SpriteorRectOp {
+    border_widths: [None, Some(5000), None, None],
+    ..Default::default()
+  }));
+  assert_eq!(result, out_of_range("border_widths", 5000, 0, 4096));
+  let result = spriteor.try_add_operation(SpriteorOperation::SpriteorShadowOp(SpriteorShadowOp {
+    blur_radius: 5000,
+    ..Default::default()
+  }));
+  assert_eq!(result, out_of_range("blur_radius", 5000, 0, 256));
+  let result = spriteor.try_add_operation(SpriteorOperation::SpriteorGlowOp(SpriteorGlowOp {
+    spread: -300,
+    ..Default::default()
+  }));
+  assert_eq!(result, out_of_range("spread", -300, -256, 256));
+  assert_eq!(
+    result.unwrap_err().to_string(),
+    "Invalid spread -300, must be in range [-256, 256]."
+  );
+}
+
+#[test]
+fn from_svg_rejects_invalid_path_data() {
+  let view_box = (0.0, 0.0, 24.0, 24.0);
+  assert_eq!(
+    SpriteorPathOp::from_svg("M0 0 L1", view_box).unwrap_err(),
+    SpriteorError::InvalidPathData { index: 7 }
+  );
+  assert_eq!(
+    SpriteorPathOp::from_svg("0 0", view_box).unwrap_err(),
+    SpriteorError::InvalidPathData { index: 0 }
+  );
+  assert_eq!(
+    SpriteorPathOp::from_svg("M0 0 L1e39 1", view_box).unwrap_err(),
+    SpriteorError::InvalidPathData { index: 6 }
+  );
+}
+
+#[test]
+fn from_svg_rejects_empty_view_box() {
+  for view_box in [
+    (0.0, 0.0, 0.0, 24.0),
+    (0.0, 0.0, 24.0, -1.0),
+    (0.0, f32::NAN, 24.0, 24.0),
+  ] {
+    assert_eq!(
+      SpriteorPathOp::from_svg("M0 0 L1 1", view_box).unwrap_err(),
+      SpriteorError::InvalidViewBox
+    );
+  }
+}
+
+#[test]
+fn encode_png_rejects_wrong_value_count() {
+  let result = ui_spriteor::encode_png(&[0; 12], 2, 2, &PngOptions::default());
+  assert_eq!(
+    result,
+    Err(SpriteorError::InvalidValueCount {
+      width: 2,
+      height: 2,
+      count: 12
+    })
+  );
+  assert_eq!(
+    result.unwrap_err().to_string(),
+    "Invalid value count 12, expected 16 for 2x2 pixels."
+  );
+}
+
+#[test]
+fn try_finalize_rejects_border_larger_than_box() {
+  let mut spriteor = small_spriteor();
+  spriteor.add_operation(SpriteorOperation::SpriteorRectOp(SpriteorRectOp {
+    point_a: Some((2, 2)),
+    point_b: Some((5, 12)),
+    border_width: 5,
+    ..Default::default()
+  }));
+  assert_eq!(
+    spriteor.try_finalize().err(),
+    Some(SpriteorError::BorderLargerThanBox {
+      border_width: 5,
+      width: 4,
+      height: 11
+    })
+  );
+}
+
+#[test]
+fn try_finalize_validates_unchecked_operations() {
+  let mut spriteor = small_spriteor();
+  spriteor.add_operation(SpriteorOperation::SpriteorRectOp(SpriteorRectOp {
+    ..Default::default()
+  }));
+  spriteor.add_operation(SpriteorOperation::SpriteorPolyOp(SpriteorPolyOp {
+    y_count: 0,
+    ..Default::default()
+  }));
+  assert_eq!(
+    spriteor.try_finalize().err(),
+    Some(SpriteorError::ZeroTileCount {
+      x_count: 1,
+      y_count: 0
+    })
+  );
+}
+
+#[test]
+fn border_wider_than_radius_does_not_panic() {
+  let mut spriteor = small_spriteor();
+  spriteor.add_operation(SpriteorOperation::SpriteorRectOp(SpriteorRectOp {
+    corner_radius: 2,
+    border_width: 4,
+    ..Default::default()
+  }));
+  assert!(spriteor.try_finalize().is_ok());
+}
+
+#[test]
+fn border_filling_the_box_draws_all_border() {
+  let mut spriteor = Spriteor::new(&SpriteorSettings::builder().size(8, 8).build());
+  spriteor.add_operation(SpriteorOperation::SpriteorRectOp(SpriteorRectOp {
+    border_width: 4,
+    border_color: Some(Paint::Solid([255, 0, 0, 255])),
+    ..Default::default()
+  }));
+  //Nested ops get the empty box inside of the border and draw nothing.
+  spriteor.add_operation(SpriteorOperation::SpriteorRectOp(SpriteorRectOp::default()));
+  spriteor.add_operation(SpriteorOperation::SpriteorHLineOp(
+    SpriteorHLineOp::default(),
+  ));
+  let values = spriteor.try_finalize().unwrap();
+  assert!(values.chunks(4).all(|pixel| pixel == [255, 0, 0, 255]));
+}
+
+#[test]
+fn rect_outside_of_parent_does_not_panic() {
+  let mut spriteor = small_spriteor();
+  spriteor.add_operation(SpriteorOperation::SpriteorRectOp(SpriteorRectOp {
+    point_a: Some((2, 2)),
+    point_b: Some((6, 6)),
+    ..Default::default()
+  }));
+  spriteor.add_operation(SpriteorOperation::SpriteorRectOp(SpriteorRectOp {
+    point_a: Some((10, 10)),
+    point_b: Some((14, 14)),
+    ..Default::default()
+  }));
+  spriteor.new_layer();
+  spriteor.add_operation(SpriteorOperation::SpriteorRectOp(SpriteorRectOp {
+    point_a: Some((8, 8)),
+    ..Default::default()
+  }));
+  spriteor.add_operation(SpriteorOperation::SpriteorHLineOp(SpriteorHLineOp {
+    y: 4000,
+    width: 4000,
+    ..Default::default()
+  }));
+  assert!(spriteor.try_finalize().is_ok());
+}
+
+#[test]
+fn widest_outside_border_stays_on_the_sprite() {
+  let mut spriteor = small_spriteor();
+  spriteor.add_operation(SpriteorOperation::SpriteorRectOp(SpriteorRectOp {
+    point_a: Some((4, 4)),
+    point_b: Some((-5, -5)),
+    border_width: 4096,
+    stroke_align: StrokeAlign::Outside,
+    ..Default::default()
+  }));
+  let values = spriteor.try_finalize().unwrap();
+  assert_eq!(values[..4], [255, 255, 255, 255]);
+  assert_eq!(values[values.len() - 4..], [255, 255, 255, 255]);
+}
+
+#[test]
+fn wide_tile_gaps_do_not_panic() {
+  let mut spriteor = small_spriteor();
+  spriteor.add_operation(SpriteorOperation::SpriteorPolyOp(SpriteorPolyOp {
+    tile_gap: (u16::MAX, u16::MAX),
+    ..Default::default()
+  }));
+  spriteor.add_operation(SpriteorOperation::SpriteorPolyOp(SpriteorPolyOp {
+    x_count: u16::MAX,
+    y_count: 2,
+    tile_gap: (u16::MAX, 2),
+    ..Default::default()
+  }));
+  assert!(spriteor.try_finalize().is_ok());
+}
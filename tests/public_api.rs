@@ -0,0 +1,403 @@
+use ui_spriteor::prelude::*;
+
+#[test]
+fn settings_builder_matches_struct_update() {
+  let built = SpriteorSettings::builder()
+    .size(24, 12)
+    .margin(2)
+    .background_color([1, 2, 3, 4])
+    .build();
+  let updated = SpriteorSettings {
+    width: 24,
+    height: 12,
+    margin: 2,
+    background_color: Some([1, 2, 3, 4]),
+  };
+  assert_eq!(built.width, updated.width);
+  assert_eq!(built.height, updated.height);
+  assert_eq!(built.margin, updated.margin);
+  assert_eq!(built.background_color, updated.background_color);
+}
+
+#[test]
+fn non_default_size() {
+  let mut spriteor = Spriteor::new(&SpriteorSettings::builder().size(64, 16).margin(1).build());
+  assert_eq!(spriteor.width(), 64);
+  assert_eq!(spriteor.height(), 16);
+  assert_eq!(spriteor.margin(), 1);
+  assert_eq!(spriteor.pixel_count(), 64 * 16);
+  assert_eq!(spriteor.finalize().len(), 64 * 16 * 4);
+}
+
+#[test]
+fn every_op_type_is_constructible() {
+  let mut spriteor = Spriteor::new(&SpriteorSettings::builder().size(16, 16).build());
+  spriteor.add_operation(SpriteorOperation::SpriteorRectOp(SpriteorRectOp {
+    border_width: 1,
+    corner_radius: 2,
+    fill_color: Some(Paint::Solid([0, 0, 255, 255])),
+    ..Default::default()
+  }));
+  spriteor.add_operation(SpriteorOperation::SpriteorPolyOp(SpriteorPolyOp {
+    x_count: 2,
+    y_count: 2,
+    polygon: FOURSTAR_POLY.to_vec(),
+    ..Default::default()
+  }));
+  spriteor.add_operation(SpriteorOperation::SpriteorHLineOp(SpriteorHLineOp {
+    y: 4,
+    ..Default::default()
+  }));
+  spriteor.add_operation(SpriteorOperation::SpriteorVLineOp(SpriteorVLineOp {
+    x: 4,
+    ..Default::default()
+  }));
+  spriteor.add_operation(SpriteorOperation::SpriteorLineOp(SpriteorLineOp {
+    points: vec![(0, 0), (-1, 4), (0, -1)],
+    width: 2,
+    cap: StrokeCap::Round,
+    ..Default::default()
+  }));
+  spriteor.add_operation(SpriteorOperation::SpriteorPathOp(SpriteorPathOp {
+    commands: vec![
+      PathCommand::MoveTo(0.0, 0.5),
+      PathCommand::CubicTo {
+        control_a: (0.0, 0.0),
+        control_b: (1.0, 0.0),
+        to: (1.0, 0.5),
+      },
+      PathCommand::Close,
+    ],
+    fill_color: Some(Paint::Solid([0, 0, 255, 255])),
+    ..Default::default()
+  }));
+  spriteor.add_operation(SpriteorOperation::SpriteorEllipseOp(SpriteorEllipseOp {
+    point_a: Some((4, 4)),
+    point_b: Some((-5, -5)),
+    inner_ratio: 0.5,
+    start_angle: 45.0,
+    end_angle: 315.0,
+    border_thickness: 1,
+    ..Default::default()
+  }));
+  spriteor.new_layer();
+  spriteor.add_operation(SpriteorOperation::NewLayer);
+  let result = spriteor.finalize();
+  assert_eq!(result.len(), 16 * 16 * 4);
+  //The centre pixel is covered by the fill of the rect at least.
+  assert_eq!(result[(8 * 16 + 8) * 4 + 3], 255);
+}
+
+#[test]
+fn shape_constants_are_unit_square() {
+  let shapes: [&[(f32, f32)]; 9] = [
+    &TRIANGLE_POLY,
+    &SQUARE_POLY,
+    &DIAMOND_POLY,
+    &PENTAGON_POLY,
+    &HEXAGON_POLY,
+    &OCTAGON_POLY,
+    &FOURSTAR_POLY,
+    &FIVESTAR_POLY,
+    &CROSS_POLY,
+  ];
+  let generated = [
+    regular_polygon(7),
+    star_polygon(5, 0.38),
+    rounded_polygon(&regular_polygon(3), 0.1),
+  ];
+  for shape in shapes
+    .into_iter()
+    .chain(generated.iter().map(|shape| &shape[..]))
+  {
+    assert!(shape
+      .iter()
+      .all(|p| (0.0..=1.0).contains(&p.0) && (0.0..=1.0).contains(&p.1)));
+  }
+}
+
+#[test]
+fn crate_root_and_spriteor_module_paths_agree() {
+  let settings: ui_spriteor::spriteor::SpriteorSettings = ui_spriteor::SpriteorSettings::default();
+  let spriteor: ui_spriteor::Spriteor = ui_spriteor::spriteor::Spriteor::new(&settings);
+  assert_eq!(spriteor.width(), 32);
+}
+
+#[test]
+fn png_output() {
+  let mut spriteor = Spriteor::new(&SpriteorSettings::builder().size(16, 8).build());
+  spriteor.add_operation(SpriteorOperation::SpriteorRectOp(SpriteorRectOp {
+    corner_radius: 3,
+    ..Default::default()
+  }));
+  let values = spriteor.finalize().clone();
+  let png = spriteor.to_png();
+  assert_eq!(png[0..8], [137, 80, 78, 71, 13, 10, 26, 10]);
+  assert_eq!(
+    png,
+    ui_spriteor::encode_png(&values, 16, 8, &PngOptions::default()).unwrap()
+  );
+
+  let options = PngOptions {
+    srgb: Some(SrgbIntent::Perceptual),
+    gamma: Some(0.45455),
+  };
+  let path = std::env::temp_dir().join(format!("ui_spriteor_{}.png", std::process::id()));
+  spriteor.write_png_with_options(&path, &options).unwrap();
+  let written = std::fs::read(&path).unwrap();
+  std::fs::remove_file(&path).unwrap();
+  assert_eq!(written, spriteor.to_png_with_options(&options));
+  assert!(written.len() > png.len());
+}
+
+#[test]
+fn gradient_paints() {
+  let mut spriteor = Spriteor::new(&SpriteorSettings::builder().size(16, 16).build());
+  spriteor.add_operation(SpriteorOperation::SpriteorRectOp(SpriteorRectOp {
+    border_width: 2,
+    fill_color: Some(Paint::LinearGradient {
+      angle: 90.0,
+      stops: vec![(0.0, [0, 0, 0, 255]), (1.0, [255, 255, 255, 255])],
+    }),
+    border_color: Some(Paint::ConicGradient {
+      center: (0.5, 0.5),
+      angle: 0.0,
+      stops: vec![(0.0, [255, 0, 0, 255]), (1.0, [0, 0, 255, 255])],
+    }),
+    ..Default::default()
+  }));
+  spriteor.add_operation(SpriteorOperation::SpriteorHLineOp(SpriteorHLineOp {
+    y: 8,
+    color: Some(Paint::RadialGradient {
+      center: (0.5, 0.5),
+      radius: 0.5,
+      stops: vec![(0.5, [0, 255, 0, 255]), (1.0, [0, 255, 0, 0])],
+    }),
+    ..Default::default()
+  }));
+  let result = spriteor.finalize();
+  let pixel = |x: usize, y: usize| &result[(y * 16 + x) * 4..(y * 16 + x) * 4 + 4];
+  //Fill gets lighter from left to right.
+  assert!(pixel(3, 4)[0] < pixel(12, 4)[0]);
+  //Border goes from red at the top to blue on the left side.
+  assert!(pixel(9, 0)[0] > pixel(9, 0)[2]);
+  assert!(pixel(0, 7)[2] > pixel(0, 7)[0]);
+  //Line is opaque green in the middle and fades out towards the ends.
+  assert_eq!(pixel(8, 8), [0, 255, 0, 255]);
+  assert!(pixel(2, 8)[1] < pixel(8, 8)[1]);
+  assert!(pixel(2, 8)[1] > pixel(2, 7)[1]);
+}
+
+#[test]
+fn shadow_and_glow_bleed_into_margin() {
+  let mut spriteor = Spriteor::new(&SpriteorSettings::builder().size(24, 24).margin(4).build());
+  spriteor.add_operation(SpriteorOperation::SpriteorRectOp(SpriteorRectOp {
+    corner_radius: 3,
+    fill_color: Some([0, 0, 255, 255].into()),
+    ..Default::default()
+  }));
+  spriteor.add_operation(SpriteorOperation::SpriteorShadowOp(SpriteorShadowOp {
+    offset: (2, 2),
+    blur_radius: 2,
+    spread: 0,
+    color: [0, 0, 0, 255],
+  }));
+  spriteor.add_operation(SpriteorOperation::SpriteorGlowOp(SpriteorGlowOp {
+    ..Default::default()
+  }));
+  let result = spriteor.finalize();
+  let pixel = |x: usize, y: usize| &result[(y * 24 + x) * 4..(y * 24 + x) * 4 + 4];
+  //The shape itself is drawn over its effects.
+  assert_eq!(pixel(12, 12), [0, 0, 255, 255]);
+  //Both effects reach outside of the rect, the shadow more so on the bottom right.
+  assert!(pixel(21, 21)[3] > 0);
+  assert!(pixel(2, 12)[3] > 0);
+  assert!(pixel(21, 12)[3] > pixel(2, 12)[3]);
+}
+
+#[test]
+fn pressed_button_with_inner_shadow_and_bevel() {
+  let mut spriteor = Spriteor::new(&SpriteorSettings::builder().size(24, 16).build());
+  spriteor.add_operation(SpriteorOperation::SpriteorRectOp(SpriteorRectOp {
+    corner_radius: 4,
+    border_width: 1,
+    fill_color: Some([120, 120, 120, 255].into()),
+    border_color: Some([60, 60, 60, 255].into()),
+    inner_shadow: Some(InnerShadow::default()),
+    bevel: Some(Bevel {
+      style: BevelStyle::Sunken,
+      ..Default::default()
+    }),
+    ..Default::default()
+  }));
+  let result = spriteor.finalize();
+  let pixel = |x: usize, y: usize| &result[(y * 24 + x) * 4..(y * 24 + x) * 4 + 4];
+  //The inner shadow darkens the fill under the top border more than above the bottom one.
+  assert!(pixel(12, 3)[0] < pixel(12, 12)[0]);
+  //A sunken bevel lit from the top left is dark along the top and light along the bottom.
+  assert!(pixel(12, 0)[0] < 60);
+  assert!(pixel(12, 15)[0] > 60);
+}
+
+#[test]
+fn tab_header_with_per_side_borders() {
+  let mut spriteor = Spriteor::new(&SpriteorSettings::builder().size(16, 8).build());
+  spriteor.add_operation(SpriteorOperation::SpriteorRectOp(SpriteorRectOp {
+    border_width: 1,
+    border_widths: [None, None, Some(0), None],
+    border_colors: [Some([255, 0, 0, 255].into()), None, None, None],
+    fill_color: Some([0, 0, 255, 255].into()),
+    ..Default::default()
+  }));
+  let result = spriteor.finalize();
+  let pixel = |x: usize, y: usize| &result[(y * 16 + x) * 4..(y * 16 + x) * 4 + 4];
+  assert_eq!(pixel(8, 0), [255, 0, 0, 255]);
+  assert_eq!(pixel(0, 4), [255, 255, 255, 255]);
+  //No bottom border, the fill reaches the bottom edge.
+  assert_eq!(pixel(8, 7), [0, 0, 255, 255]);
+}
+
+#[test]
+fn tab_with_elliptical_top_corners() {
+  let mut spriteor = Spriteor::new(&SpriteorSettings::builder().size(32, 12).build());
+  spriteor.add_operation(SpriteorOperation::SpriteorRectOp(SpriteorRectOp {
+    corner_radii: [Some((8, 4)), Some((8, 4)), Some((0, 0)), Some((0, 0))],
+    fill_color: Some([0, 0, 255, 255].into()),
+    ..Default::default()
+  }));
+  let result = spriteor.finalize();
+  let alpha = |x: usize, y: usize| result[(y * 32 + x) * 4 + 3];
+  assert_eq!(alpha(0, 0), 0);
+  assert_eq!(alpha(31, 0), 0);
+  assert_eq!(alpha(0, 11), 255);
+  assert_eq!(alpha(31, 11), 255);
+  //Wider than tall, so the corner still cuts in at the end of the second row.
+  assert!(alpha(0, 2) < 128);
+  assert_eq!(alpha(2, 4), 255);
+}
+
+#[test]
+fn sci_fi_panel_corners() {
+  let mut spriteor = Spriteor::new(&SpriteorSettings::builder().size(16, 16).build());
+  spriteor.add_operation(SpriteorOperation::SpriteorRectOp(SpriteorRectOp {
+    corner_radius: 4,
+    border_width: 1,
+    corner_styles: [
+      CornerStyle::Chamfer,
+      CornerStyle::Notch,
+      CornerStyle::Scoop,
+      CornerStyle::Round,
+    ],
+    anti_alias: false,
+    ..Default::default()
+  }));
+  let result = spriteor.finalize();
+  let alpha = |x: usize, y: usize| result[(y * 16 + x) * 4 + 3];
+  assert_eq!(alpha(1, 1), 0);
+  //A notch removes the whole corner square, the other styles keep part of it.
+  assert_eq!(alpha(12, 3), 0);
+  assert_eq!(alpha(2, 2), 255);
+  //The scoop cuts deeper into the corner than the round one.
+  assert_eq!(alpha(13, 13), 0);
+  assert_eq!(alpha(12, 12), 255);
+  assert_eq!(alpha(0, 15), 0);
+  assert_eq!(alpha(2, 13), 255);
+}
+
+#[test]
+fn dashed_focus_ring_and_outset_panel() {
+  let mut spriteor = Spriteor::new(&SpriteorSettings::builder().size(16, 16).build());
+  spriteor.add_operation(SpriteorOperation::SpriteorRectOp(SpriteorRectOp {
+    corner_radius: 3,
+    border_width: 1,
+    border_style: BorderStyle::Dashed { dash: 2, gap: 2 },
+    fill_color: Some(Paint::Solid([0, 0, 0, 0])),
+    ..Default::default()
+  }));
+  let result = spriteor.finalize();
+  let alpha = |x: usize, y: usize| result[(y * 16 + x) * 4 + 3];
+  let top: Vec<u8> = (3..13).map(|x| alpha(x, 0)).collect();
+  assert!(top.contains(&0) && top.contains(&255));
+
+  let mut spriteor = Spriteor::new(&SpriteorSettings::builder().size(16, 16).build());
+  spriteor.add_operation(SpriteorOperation::SpriteorRectOp(SpriteorRectOp {
+    border_width: 2,
+    border_color: Some(Paint::Solid([192, 192, 192, 255])),
+    border_style: BorderStyle::Outset,
+    ..Default::default()
+  }));
+  let result = spriteor.finalize();
+  let red = |x: usize, y: usize| result[(y * 16 + x) * 4];
+  assert!(red(8, 0) > 192 && red(0, 8) > 192);
+  assert!(red(8, 15) < 192 && red(15, 8) < 192);
+}
+
+#[test]
+fn outside_stroke_keeps_fill_size() {
+  let settings = SpriteorSettings::builder().size(16, 16).margin(2).build();
+  let draw = |stroke_align: StrokeAlign| {
+    let mut spriteor = Spriteor::new(&settings);
+    spriteor.add_operation(SpriteorOperation::SpriteorRectOp(SpriteorRectOp {
+      border_width: 2,
+      stroke_align,
+      fill_color: Some(Paint::Solid([0, 0, 255, 255])),
+      ..Default::default()
+    }));
+    spriteor.finalize().clone()
+  };
+  let is_fill =
+    |values: &[u8], x: usize, y: usize| values[(y * 16 + x) * 4..][..4] == [0, 0, 255, 255];
+  let inside = draw(StrokeAlign::Inside);
+  let outside = draw(StrokeAlign::Outside);
+  assert_eq!(inside[3], 0);
+  assert!(!is_fill(&inside, 2, 8) && is_fill(&inside, 4, 8));
+  //The outside border fills the margin and the fill reaches the edge of the margin.
+  assert_eq!(outside[3], 255);
+  assert!(is_fill(&outside, 2, 8) && is_fill(&outside, 13, 8));
+  assert!(!is_fill(&outside, 1, 8) && !is_fill(&outside, 14, 8));
+}
+
+#[test]
+fn arrow_icon_contained_in_wide_button() {
+  let settings = SpriteorSettings::builder().size(24, 12).margin(0).build();
+  let mut spriteor = Spriteor::new(&settings);
+  spriteor.add_operation(SpriteorOperation::SpriteorPolyOp(SpriteorPolyOp {
+    point_a: Some((12, 0)),
+    point_b: Some((23, 11)),
+    polygon: TRIANGLE_POLY.to_vec(),
+    fit: PolyFit::Contain,
+    align: (1.0, 0.5),
+    fill_color: Some(Paint::Solid([255, 0, 0, 255])),
+    anti_alias: false,
+    ..Default::default()
+  }));
+  let values = spriteor.finalize();
+  let alpha = |x: usize, y: usize| values[(y * 24 + x) * 4 + 3];
+  //The 12 x 12 area fits the triangle undistorted, the left half of the button stays empty.
+  assert!((0..12).all(|y| (0..12).all(|x| alpha(x, y) == 0)));
+  assert_eq!(alpha(13, 11), 255);
+  assert_eq!(alpha(23, 11), 255);
+  assert_eq!((alpha(17, 2), alpha(18, 2)), (255, 255));
+  assert_eq!(alpha(12, 0), 0);
+}
+
+#[test]
+fn svg_ring_icon() {
+  //Outer circle counterclockwise and inner circle clockwise, so the nonzero rule cuts a hole.
+  let ring = SpriteorPathOp::from_svg(
+    "M12 2A10 10 0 1 0 12 22 10 10 0 1 0 12 2zm0 6a4 4 0 1 1 0 8 4 4 0 0 1 0-8z",
+    (0.0, 0.0, 24.0, 24.0),
+  )
+  .unwrap();
+  let settings = SpriteorSettings::builder().size(24, 24).margin(0).build();
+  let mut spriteor = Spriteor::new(&settings);
+  spriteor.add_operation(SpriteorOperation::SpriteorPathOp(SpriteorPathOp {
+    anti_alias: false,
+    ..ring
+  }));
+  let values = spriteor.finalize();
+  let alpha = |x: usize, y: usize| values[(y * 24 + x) * 4 + 3];
+  assert_eq!((alpha(12, 1), alpha(12, 4), alpha(12, 12)), (0, 255, 0));
+  assert_eq!((alpha(2, 12), alpha(4, 12), alpha(20, 12)), (255, 255, 255));
+  assert_eq!(alpha(0, 0), 0);
+}